@@ -10,7 +10,7 @@ extern crate curl_sys;
 extern crate openssl_sys;
 extern crate libz_sys as libz;
 
-use libc::{c_int, c_char, c_uint, size_t, c_uchar, c_void};
+use libc::{c_int, c_char, c_uint, size_t, c_uchar, c_void, c_ushort};
 
 pub const GIT_OID_RAWSZ: usize = 20;
 pub const GIT_OID_HEXSZ: usize = GIT_OID_RAWSZ * 2;
@@ -18,6 +18,11 @@ pub const GIT_CLONE_OPTIONS_VERSION: c_uint = 1;
 pub const GIT_STASH_APPLY_OPTIONS_VERSION: c_uint = 1;
 pub const GIT_CHECKOUT_OPTIONS_VERSION: c_uint = 1;
 pub const GIT_MERGE_OPTIONS_VERSION: c_uint = 1;
+pub const GIT_MERGE_FILE_OPTIONS_VERSION: c_uint = 1;
+pub const GIT_CHERRYPICK_OPTIONS_VERSION: c_uint = 1;
+pub const GIT_REVERT_OPTIONS_VERSION: c_uint = 1;
+pub const GIT_REBASE_OPTIONS_VERSION: c_uint = 1;
+pub const GIT_REBASE_NO_OPERATION: usize = !0;
 pub const GIT_REMOTE_CALLBACKS_VERSION: c_uint = 1;
 pub const GIT_STATUS_OPTIONS_VERSION: c_uint = 1;
 pub const GIT_BLAME_OPTIONS_VERSION: c_uint = 1;
@@ -86,8 +91,9 @@ pub enum git_packbuilder {}
 pub enum git_odb {}
 pub enum git_odb_stream {}
 pub enum git_odb_object {}
-pub enum git_odb_writepack {}
 pub enum git_worktree {}
+pub enum git_filter_list {}
+pub enum git_rebase {}
 
 #[repr(C)]
 pub struct git_revspec {
@@ -557,6 +563,11 @@ pub struct git_status_options {
     pub baseline: *mut git_tree,
 }
 
+pub const GIT_DIFF_FLAG_BINARY: u32 = 1 << 0;
+pub const GIT_DIFF_FLAG_NOT_BINARY: u32 = 1 << 1;
+pub const GIT_DIFF_FLAG_VALID_ID: u32 = 1 << 2;
+pub const GIT_DIFF_FLAG_EXISTS: u32 = 1 << 3;
+
 #[repr(C)]
 pub struct git_diff_delta {
     pub status: git_delta_t,
@@ -885,6 +896,15 @@ pub type git_tag_foreach_cb = extern fn(name: *const c_char,
                                         oid: *mut git_oid,
                                         payload: *mut c_void) -> c_int;
 
+git_enum! {
+    pub enum git_indexcap_t {
+        GIT_INDEXCAP_IGNORE_CASE = 1,
+        GIT_INDEXCAP_NO_FILEMODE = 2,
+        GIT_INDEXCAP_NO_SYMLINKS = 4,
+    }
+}
+pub const GIT_INDEXCAP_FROM_OWNER: c_int = -1;
+
 git_enum! {
     pub enum git_index_add_option_t {
         GIT_INDEX_ADD_DEFAULT = 0,
@@ -1161,6 +1181,24 @@ pub const GIT_DIFF_FIND_EXACT_MATCH_ONLY: u32 = 1 << 14;
 pub const GIT_DIFF_BREAK_REWRITES_FOR_RENAMES_ONLY : u32 = 1 << 15;
 pub const GIT_DIFF_FIND_REMOVE_UNMODIFIED: u32 = 1 << 16;
 
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct git_diff_format_email_options {
+    pub version: c_uint,
+    pub flags: u32,
+    pub patch_no: size_t,
+    pub total_patches: size_t,
+    pub id: *const git_oid,
+    pub summary: *const c_char,
+    pub body: *const c_char,
+    pub author: *const git_signature,
+}
+
+pub const GIT_DIFF_FORMAT_EMAIL_OPTIONS_VERSION: c_uint = 1;
+
+pub const GIT_DIFF_FORMAT_EMAIL_NONE: u32 = 0;
+pub const GIT_DIFF_FORMAT_EMAIL_EXCLUDE_SUBJECT_PATCH_MARKER: u32 = 1 << 0;
+
 #[repr(C)]
 pub struct git_diff_binary {
     pub contains_data: c_uint,
@@ -1229,6 +1267,70 @@ git_enum! {
     }
 }
 
+#[repr(C)]
+pub struct git_merge_file_options {
+    pub version: c_uint,
+    pub ancestor_label: *const c_char,
+    pub our_label: *const c_char,
+    pub their_label: *const c_char,
+    pub favor: git_merge_file_favor_t,
+    pub flags: git_merge_file_flag_t,
+    pub marker_size: c_ushort,
+}
+
+#[repr(C)]
+pub struct git_merge_file_result {
+    pub automergeable: c_uint,
+    pub path: *const c_char,
+    pub mode: c_uint,
+    pub ptr: *const c_char,
+    pub len: size_t,
+}
+
+#[repr(C)]
+pub struct git_cherrypick_options {
+    pub version: c_uint,
+    pub mainline: c_uint,
+    pub merge_opts: git_merge_options,
+    pub checkout_opts: git_checkout_options,
+}
+
+#[repr(C)]
+pub struct git_revert_options {
+    pub version: c_uint,
+    pub mainline: c_uint,
+    pub merge_opts: git_merge_options,
+    pub checkout_opts: git_checkout_options,
+}
+
+#[repr(C)]
+pub struct git_rebase_options {
+    pub version: c_uint,
+    pub quiet: c_int,
+    pub inmemory: c_int,
+    pub rewrite_notes_ref: *const c_char,
+    pub merge_options: git_merge_options,
+    pub checkout_options: git_checkout_options,
+}
+
+git_enum! {
+    pub enum git_rebase_operation_t {
+        GIT_REBASE_OPERATION_PICK = 0,
+        GIT_REBASE_OPERATION_REWORD,
+        GIT_REBASE_OPERATION_EDIT,
+        GIT_REBASE_OPERATION_SQUASH,
+        GIT_REBASE_OPERATION_FIXUP,
+        GIT_REBASE_OPERATION_EXEC,
+    }
+}
+
+#[repr(C)]
+pub struct git_rebase_operation {
+    pub kind: git_rebase_operation_t,
+    pub id: git_oid,
+    pub exec: *const c_char,
+}
+
 git_enum! {
     pub enum git_merge_analysis_t {
         GIT_MERGE_ANALYSIS_NONE = 0,
@@ -1354,6 +1456,28 @@ pub struct git_odb_backend {
     pub free: extern fn(*mut git_odb_backend),
 }
 
+#[repr(C)]
+pub struct git_odb_expand_id {
+    pub id: git_oid,
+    pub length: c_ushort,
+    pub kind: git_otype,
+}
+
+#[repr(C)]
+pub struct git_odb_writepack {
+    pub backend: *mut git_odb_backend,
+
+    pub append: extern fn(*mut git_odb_writepack,
+                          *const c_void,
+                          size_t,
+                          *mut git_transfer_progress) -> c_int,
+
+    pub commit: extern fn(*mut git_odb_writepack,
+                          *mut git_transfer_progress) -> c_int,
+
+    pub free: extern fn(*mut git_odb_writepack),
+}
+
 #[repr(C)]
 pub struct git_refdb_backend {
     pub version: c_uint,
@@ -1506,6 +1630,22 @@ git_enum! {
     }
 }
 
+git_enum! {
+    pub enum git_filter_mode_t {
+        GIT_FILTER_TO_WORKTREE = 0,
+        GIT_FILTER_TO_ODB = 1,
+    }
+}
+pub const GIT_FILTER_SMUDGE: git_filter_mode_t = GIT_FILTER_TO_WORKTREE;
+pub const GIT_FILTER_CLEAN: git_filter_mode_t = GIT_FILTER_TO_ODB;
+
+git_enum! {
+    pub enum git_filter_flag_t {
+        GIT_FILTER_DEFAULT = 0,
+        GIT_FILTER_ALLOW_UNSAFE = 1 << 0,
+    }
+}
+
 git_enum! {
     pub enum git_stash_flags {
         GIT_STASH_DEFAULT = 0,
@@ -1552,16 +1692,67 @@ pub type git_stash_cb = extern fn(index: size_t,
                                   stash_id: *const git_oid,
                                   payload: *mut c_void) -> c_int;
 
+pub const GIT_ATTR_CHECK_FILE_THEN_INDEX: u32 = 0;
+pub const GIT_ATTR_CHECK_INDEX_THEN_FILE: u32 = 1;
+pub const GIT_ATTR_CHECK_INDEX_ONLY: u32 = 2;
+pub const GIT_ATTR_CHECK_NO_SYSTEM: u32 = 1 << 2;
+
+pub type git_attr_foreach_cb = extern fn(name: *const c_char,
+                                         value: *const c_char,
+                                         payload: *mut c_void) -> c_int;
+
+pub type git_repository_mergehead_foreach_cb = extern fn(oid: *const git_oid,
+                                                         payload: *mut c_void)
+                                                         -> c_int;
+
+pub type git_repository_fetchhead_foreach_cb = extern fn(*const c_char,
+                                                         *const c_char,
+                                                         *const git_oid,
+                                                         c_uint,
+                                                         *mut c_void)
+                                                         -> c_int;
+
 pub type git_packbuilder_foreach_cb = extern fn(*const c_void, size_t,
                                                 *mut c_void) -> c_int;
 
 pub type git_odb_foreach_cb = extern fn(id: *const git_oid, payload: *mut c_void) -> c_int;
 
+git_enum! {
+    pub enum git_libgit2_opt_t {
+        GIT_OPT_GET_MWINDOW_SIZE = 0,
+        GIT_OPT_SET_MWINDOW_SIZE,
+        GIT_OPT_GET_MWINDOW_MAPPED_LIMIT,
+        GIT_OPT_SET_MWINDOW_MAPPED_LIMIT,
+        GIT_OPT_GET_SEARCH_PATH,
+        GIT_OPT_SET_SEARCH_PATH,
+        GIT_OPT_SET_CACHE_OBJECT_LIMIT,
+        GIT_OPT_SET_CACHE_MAX_SIZE,
+        GIT_OPT_ENABLE_CACHING,
+        GIT_OPT_GET_CACHED_MEMORY,
+        GIT_OPT_GET_TEMPLATE_PATH,
+        GIT_OPT_SET_TEMPLATE_PATH,
+        GIT_OPT_SET_SSL_CERT_LOCATIONS,
+        GIT_OPT_SET_USER_AGENT,
+        GIT_OPT_ENABLE_STRICT_OBJECT_CREATION,
+        GIT_OPT_ENABLE_STRICT_SYMBOLIC_REF_CREATION,
+        GIT_OPT_SET_SSL_CIPHERS,
+        GIT_OPT_GET_USER_AGENT,
+        GIT_OPT_ENABLE_OFS_DELTA,
+        GIT_OPT_ENABLE_FSYNC_GITDIR,
+        GIT_OPT_GET_WINDOWS_SHAREMODE,
+        GIT_OPT_SET_WINDOWS_SHAREMODE,
+        GIT_OPT_ENABLE_STRICT_HASH_VERIFICATION,
+    }
+}
+
 extern {
     // threads
     pub fn git_libgit2_init() -> c_int;
     pub fn git_libgit2_shutdown() -> c_int;
 
+    // options
+    pub fn git_libgit2_opts(option: c_int, ...) -> c_int;
+
     // repository
     pub fn git_repository_new(out: *mut *mut git_repository) -> c_int;
     pub fn git_repository_free(repo: *mut git_repository);
@@ -1604,6 +1795,8 @@ extern {
     pub fn git_repository_is_empty(repo: *mut git_repository) -> c_int;
     pub fn git_repository_is_shallow(repo: *mut git_repository) -> c_int;
     pub fn git_repository_path(repo: *const git_repository) -> *const c_char;
+    pub fn git_repository_commondir(repo: *const git_repository)
+                                    -> *const c_char;
     pub fn git_repository_state(repo: *mut git_repository) -> c_int;
     pub fn git_repository_workdir(repo: *const git_repository) -> *const c_char;
     pub fn git_repository_set_workdir(repo: *mut git_repository,
@@ -1618,6 +1811,12 @@ extern {
                                   repo: *mut git_repository) -> c_int;
 
     pub fn git_repository_message_remove(repo: *mut git_repository) -> c_int;
+    pub fn git_repository_mergehead_foreach(repo: *mut git_repository,
+                            callback: git_repository_mergehead_foreach_cb,
+                            payload: *mut c_void) -> c_int;
+    pub fn git_repository_fetchhead_foreach(repo: *mut git_repository,
+                            callback: git_repository_fetchhead_foreach_cb,
+                            payload: *mut c_void) -> c_int;
     pub fn git_repository_config(out: *mut *mut git_config,
                                  repo: *mut git_repository) -> c_int;
     pub fn git_repository_set_config(repo: *mut git_repository,
@@ -1846,6 +2045,8 @@ extern {
                              ref2: *const git_reference) -> c_int;
     pub fn git_reference_delete(r: *mut git_reference) -> c_int;
     pub fn git_reference_free(r: *mut git_reference);
+    pub fn git_reference_dup(dest: *mut *mut git_reference,
+                             source: *mut git_reference) -> c_int;
     pub fn git_reference_is_branch(r: *const git_reference) -> c_int;
     pub fn git_reference_is_note(r: *const git_reference) -> c_int;
     pub fn git_reference_is_remote(r: *const git_reference) -> c_int;
@@ -1971,6 +2172,9 @@ extern {
     pub fn git_submodule_open(repo: *mut *mut git_repository,
                               submodule: *mut git_submodule) -> c_int;
     pub fn git_submodule_path(submodule: *mut git_submodule) -> *const c_char;
+    pub fn git_submodule_repo_init(repo: *mut *mut git_repository,
+                                   submodule: *const git_submodule,
+                                   use_gitlink: c_int) -> c_int;
     pub fn git_submodule_reload(submodule: *mut git_submodule,
                                 force: c_int) -> c_int;
     pub fn git_submodule_set_ignore(repo: *mut git_repository,
@@ -2093,6 +2297,27 @@ extern {
     pub fn git_buf_set(buffer: *mut git_buf, data: *const c_void,
                        datalen: size_t) -> c_int;
 
+    // filter
+    pub fn git_filter_list_load(filters: *mut *mut git_filter_list,
+                                repo: *mut git_repository,
+                                blob: *mut git_blob,
+                                path: *const c_char,
+                                mode: git_filter_mode_t,
+                                flags: u32) -> c_int;
+    pub fn git_filter_list_contains(filters: *mut git_filter_list,
+                                    name: *const c_char) -> c_int;
+    pub fn git_filter_list_apply_to_data(out: *mut git_buf,
+                                         filters: *mut git_filter_list,
+                                         in_: *mut git_buf) -> c_int;
+    pub fn git_filter_list_apply_to_file(out: *mut git_buf,
+                                         filters: *mut git_filter_list,
+                                         repo: *mut git_repository,
+                                         path: *const c_char) -> c_int;
+    pub fn git_filter_list_apply_to_blob(out: *mut git_buf,
+                                         filters: *mut git_filter_list,
+                                         blob: *mut git_blob) -> c_int;
+    pub fn git_filter_list_free(filters: *mut git_filter_list);
+
     // commit
     pub fn git_commit_author(commit: *const git_commit) -> *const git_signature;
     pub fn git_commit_committer(commit: *const git_commit) -> *const git_signature;
@@ -2115,6 +2340,7 @@ extern {
     pub fn git_commit_parentcount(commit: *const git_commit) -> c_uint;
     pub fn git_commit_raw_header(commit: *const git_commit) -> *const c_char;
     pub fn git_commit_summary(commit: *mut git_commit) -> *const c_char;
+    pub fn git_commit_body(commit: *mut git_commit) -> *const c_char;
     pub fn git_commit_time(commit: *const git_commit) -> git_time_t;
     pub fn git_commit_time_offset(commit: *const git_commit) -> c_int;
     pub fn git_commit_tree(tree_out: *mut *mut git_tree,
@@ -2138,6 +2364,16 @@ extern {
                              tree: *const git_tree,
                              parent_count: size_t,
                              parents: *mut *const git_commit) -> c_int;
+    pub fn git_commit_create_from_ids(id: *mut git_oid,
+                                      repo: *mut git_repository,
+                                      update_ref: *const c_char,
+                                      author: *const git_signature,
+                                      committer: *const git_signature,
+                                      message_encoding: *const c_char,
+                                      message: *const c_char,
+                                      tree: *const git_oid,
+                                      parent_count: size_t,
+                                      parents: *mut *const git_oid) -> c_int;
     pub fn git_commit_header_field(out: *mut git_buf,
                                    commit: *const git_commit,
                                    field: *const c_char) -> c_int;
@@ -2184,6 +2420,9 @@ extern {
                                    upstream_name: *const c_char) -> c_int;
     pub fn git_branch_upstream(out: *mut *mut git_reference,
                                branch: *const git_reference) -> c_int;
+    pub fn git_branch_upstream_name(out: *mut git_buf,
+                                    repo: *mut git_repository,
+                                    refname: *const c_char) -> c_int;
 
     // index
     pub fn git_index_add(index: *mut git_index,
@@ -2209,18 +2448,26 @@ extern {
     pub fn git_index_find(at_pos: *mut size_t,
                           index: *mut git_index,
                           path: *const c_char) -> c_int;
+    pub fn git_index_find_prefix(at_pos: *mut size_t,
+                                 index: *mut git_index,
+                                 prefix: *const c_char) -> c_int;
     pub fn git_index_free(index: *mut git_index);
     pub fn git_index_get_byindex(index: *mut git_index,
                                  n: size_t) -> *const git_index_entry;
     pub fn git_index_get_bypath(index: *mut git_index,
                                 path: *const c_char,
                                 stage: c_int) -> *const git_index_entry;
+    pub fn git_index_caps(index: *const git_index) -> c_int;
+    pub fn git_index_set_caps(index: *mut git_index, caps: c_int) -> c_int;
     pub fn git_index_has_conflicts(index: *const git_index) -> c_int;
     pub fn git_index_new(index: *mut *mut git_index) -> c_int;
     pub fn git_index_open(index: *mut *mut git_index,
                           index_path: *const c_char) -> c_int;
     pub fn git_index_path(index: *const git_index) -> *const c_char;
     pub fn git_index_read(index: *mut git_index, force: c_int) -> c_int;
+    pub fn git_index_version(index: *mut git_index) -> c_uint;
+    pub fn git_index_set_version(index: *mut git_index,
+                                 version: c_uint) -> c_int;
     pub fn git_index_read_tree(index: *mut git_index,
                                tree: *const git_tree) -> c_int;
     pub fn git_index_remove(index: *mut git_index,
@@ -2452,6 +2699,16 @@ extern {
                            our_tree: *const git_tree,
                            their_tree: *const git_tree,
                            opts: *const git_merge_options) -> c_int;
+    pub fn git_merge_file_init_options(opts: *mut git_merge_file_options,
+                                       version: c_uint) -> c_int;
+    pub fn git_merge_file_from_index(out: *mut git_merge_file_result,
+                                     repo: *mut git_repository,
+                                     ancestor: *const git_index_entry,
+                                     ours: *const git_index_entry,
+                                     theirs: *const git_index_entry,
+                                     opts: *const git_merge_file_options)
+                                     -> c_int;
+    pub fn git_merge_file_result_free(file_result: *mut git_merge_file_result);
     pub fn git_repository_state_cleanup(repo: *mut git_repository) -> c_int;
 
     // merge analysis
@@ -2462,6 +2719,66 @@ extern {
                               their_heads: *mut *const git_annotated_commit,
                               their_heads_len: usize) -> c_int;
 
+    // cherrypick
+    pub fn git_cherrypick_init_options(opts: *mut git_cherrypick_options,
+                                       version: c_uint) -> c_int;
+    pub fn git_cherrypick(repo: *mut git_repository,
+                          commit: *mut git_commit,
+                          options: *const git_cherrypick_options) -> c_int;
+    pub fn git_cherrypick_commit(out: *mut *mut git_index,
+                                 repo: *mut git_repository,
+                                 cherrypick_commit: *mut git_commit,
+                                 our_commit: *mut git_commit,
+                                 mainline: c_uint,
+                                 merge_options: *const git_merge_options)
+                                 -> c_int;
+
+    // revert
+    pub fn git_revert_init_options(opts: *mut git_revert_options,
+                                   version: c_uint) -> c_int;
+    pub fn git_revert(repo: *mut git_repository,
+                      commit: *mut git_commit,
+                      given_opts: *const git_revert_options) -> c_int;
+    pub fn git_revert_commit(out: *mut *mut git_index,
+                             repo: *mut git_repository,
+                             revert_commit: *mut git_commit,
+                             our_commit: *mut git_commit,
+                             mainline: c_uint,
+                             merge_options: *const git_merge_options)
+                             -> c_int;
+
+    // rebase
+    pub fn git_rebase_init_options(opts: *mut git_rebase_options,
+                                   version: c_uint) -> c_int;
+    pub fn git_rebase_init(out: *mut *mut git_rebase,
+                           repo: *mut git_repository,
+                           branch: *const git_annotated_commit,
+                           upstream: *const git_annotated_commit,
+                           onto: *const git_annotated_commit,
+                           opts: *const git_rebase_options) -> c_int;
+    pub fn git_rebase_open(out: *mut *mut git_rebase,
+                           repo: *mut git_repository,
+                           opts: *const git_rebase_options) -> c_int;
+    pub fn git_rebase_operation_entrycount(rebase: *mut git_rebase) -> size_t;
+    pub fn git_rebase_operation_current(rebase: *mut git_rebase) -> size_t;
+    pub fn git_rebase_operation_byindex(rebase: *mut git_rebase,
+                                        idx: size_t)
+                                        -> *mut git_rebase_operation;
+    pub fn git_rebase_next(operation: *mut *mut git_rebase_operation,
+                           rebase: *mut git_rebase) -> c_int;
+    pub fn git_rebase_inmemory_index(index: *mut *mut git_index,
+                                     rebase: *mut git_rebase) -> c_int;
+    pub fn git_rebase_commit(id: *mut git_oid,
+                             rebase: *mut git_rebase,
+                             author: *const git_signature,
+                             committer: *const git_signature,
+                             message_encoding: *const c_char,
+                             message: *const c_char) -> c_int;
+    pub fn git_rebase_abort(rebase: *mut git_rebase) -> c_int;
+    pub fn git_rebase_finish(rebase: *mut git_rebase,
+                             signature: *const git_signature) -> c_int;
+    pub fn git_rebase_free(rebase: *mut git_rebase);
+
     // notes
     pub fn git_note_author(note: *const git_note) -> *const git_signature;
     pub fn git_note_committer(note: *const git_note) -> *const git_signature;
@@ -2628,6 +2945,13 @@ extern {
                                  options: *const git_diff_find_options) -> c_int;
     pub fn git_diff_find_init_options(opts: *mut git_diff_find_options,
                                       version: c_uint) -> c_int;
+    pub fn git_diff_format_email(out: *mut git_buf,
+                                 diff: *mut git_diff,
+                                 opts: *const git_diff_format_email_options)
+                                 -> c_int;
+    pub fn git_diff_format_email_init_options(
+        opts: *mut git_diff_format_email_options,
+        version: c_uint) -> c_int;
     pub fn git_diff_foreach(diff: *mut git_diff,
                             file_cb: git_diff_file_cb,
                             binary_cb: Option<git_diff_binary_cb>,
@@ -2635,6 +2959,9 @@ extern {
                             line_cb: Option<git_diff_line_cb>,
                             payload: *mut c_void) -> c_int;
     pub fn git_diff_free(diff: *mut git_diff);
+    pub fn git_diff_from_buffer(diff: *mut *mut git_diff,
+                                content: *const c_char,
+                                content_len: size_t) -> c_int;
     pub fn git_diff_get_delta(diff: *const git_diff,
                               idx: size_t) -> *const git_diff_delta;
     pub fn git_diff_get_stats(out: *mut *mut git_diff_stats,
@@ -2660,6 +2987,9 @@ extern {
                           format: git_diff_format_t,
                           print_cb: git_diff_line_cb,
                           payload: *mut c_void) -> c_int;
+    pub fn git_diff_to_buf(out: *mut git_buf,
+                           diff: *mut git_diff,
+                           format: git_diff_format_t) -> c_int;
     pub fn git_diff_stats_deletions(stats: *const git_diff_stats) -> size_t;
     pub fn git_diff_stats_files_changed(stats: *const git_diff_stats) -> size_t;
     pub fn git_diff_stats_free(stats: *mut git_diff_stats);
@@ -2898,8 +3228,15 @@ extern {
 
     pub fn git_odb_exists(odb: *mut git_odb,
                           oid: *const git_oid) -> c_int;
+    pub fn git_odb_expand_ids(db: *mut git_odb,
+                              ids: *mut git_odb_expand_id,
+                              count: size_t) -> c_int;
 
     pub fn git_odb_refresh(odb: *mut git_odb) -> c_int;
+    pub fn git_odb_write_pack(out: *mut *mut git_odb_writepack,
+                              db: *mut git_odb,
+                              progress_cb: Option<git_transfer_progress_cb>,
+                              progress_payload: *mut c_void) -> c_int;
 
     pub fn git_odb_object_id(obj: *mut git_odb_object) -> *const git_oid;
     pub fn git_odb_object_size(obj: *mut git_odb_object) -> size_t;
@@ -2959,6 +3296,22 @@ extern {
     pub fn git_refdb_set_backend(refdb: *mut git_refdb, backend: *mut git_refdb_backend) -> c_int;
     pub fn git_refdb_compress(refdb: *mut git_refdb) -> c_int;
     pub fn git_refdb_free(refdb: *mut git_refdb);
+
+    // attr
+    pub fn git_attr_foreach(repo: *mut git_repository,
+                            flags: u32,
+                            path: *const c_char,
+                            callback: git_attr_foreach_cb,
+                            payload: *mut c_void) -> c_int;
+
+    // worktree
+    pub fn git_worktree_list(out: *mut git_strarray,
+                             repo: *mut git_repository) -> c_int;
+    pub fn git_worktree_lookup(out: *mut *mut git_worktree,
+                               repo: *mut git_repository,
+                               name: *const c_char) -> c_int;
+    pub fn git_worktree_validate(wt: *const git_worktree) -> c_int;
+    pub fn git_worktree_free(wt: *mut git_worktree);
 }
 
 pub fn init() {
@@ -276,8 +276,11 @@ impl<'blame> ExactSizeIterator for BlameIter<'blame> {}
 #[cfg(test)]
 mod tests {
     use std::fs::{self, File};
+    use std::io::prelude::*;
     use std::path::Path;
 
+    use BlameOptions;
+
     #[test]
     fn smoke() {
         let (_td, repo) = ::test::repo_init();
@@ -311,5 +314,84 @@ mod tests {
         assert!(!hunk.is_boundary())
     }
 
+    #[test]
+    fn boundary() {
+        let (_td, repo) = ::test::repo_init();
+        let root = repo.path().parent().unwrap();
+        let commit = |contents: &[u8]| {
+            File::create(&root.join("file")).unwrap()
+                .write_all(contents).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("file")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = repo.signature().unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "commit", &tree,
+                        &[&head]).unwrap()
+        };
+        let first = commit(b"line 1\n");
+        let second = commit(b"line 1\nline 2\n");
+
+        let mut opts = BlameOptions::new();
+        opts.oldest_commit(first);
+        let blame = repo.blame_file(Path::new("file"), Some(&mut opts))
+                        .unwrap();
+        assert_eq!(blame.len(), 2);
+
+        let hunk = blame.get_line(1).unwrap();
+        assert_eq!(hunk.final_commit_id(), first);
+        assert_eq!(hunk.orig_start_line(), 1);
+        assert_eq!(hunk.lines_in_hunk(), 1);
+        assert!(hunk.is_boundary());
+
+        let hunk = blame.get_line(2).unwrap();
+        assert_eq!(hunk.final_commit_id(), second);
+        assert_eq!(hunk.final_start_line(), 2);
+        assert_eq!(hunk.path(), Some(Path::new("file")));
+        assert!(!hunk.is_boundary());
+    }
+
+    #[test]
+    fn follows_renames() {
+        let (_td, repo) = ::test::repo_init();
+        let root = repo.path().parent().unwrap();
+        let original = (1..11).map(|i| format!("original line {}\n", i))
+                              .collect::<String>();
+        let commit = |remove: Option<&str>, path: &str, contents: &str| {
+            let mut index = repo.index().unwrap();
+            if let Some(remove) = remove {
+                fs::remove_file(root.join(remove)).unwrap();
+                index.remove_path(Path::new(remove)).unwrap();
+            }
+            File::create(&root.join(path)).unwrap()
+                .write_all(contents.as_bytes()).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = repo.signature().unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "commit", &tree,
+                        &[&head]).unwrap()
+        };
+        let first = commit(None, "old", &original);
+        let renamed = commit(Some("old"), "new",
+                             &format!("{}added line\n", original));
+
+        let mut opts = BlameOptions::new();
+        opts.track_copies_same_file(true)
+            .track_copies_same_commit_moves(true)
+            .track_copies_same_commit_copies(true);
+        let blame = repo.blame_file(Path::new("new"), Some(&mut opts))
+                        .unwrap();
+
+        let hunk = blame.get_line(1).unwrap();
+        assert_eq!(hunk.final_commit_id(), first);
+        assert_eq!(hunk.lines_in_hunk(), 10);
+        assert_eq!(hunk.path(), Some(Path::new("old")));
+
+        let hunk = blame.get_line(11).unwrap();
+        assert_eq!(hunk.final_commit_id(), renamed);
+        assert_eq!(hunk.path(), Some(Path::new("new")));
+    }
 }
 
@@ -26,6 +26,9 @@ impl<'repo> Blob<'repo> {
     }
 
     /// Get the content of this blob.
+    ///
+    /// These are the raw bytes stored in the object database, without any
+    /// filters (such as line ending conversion) applied.
     pub fn content(&self) -> &[u8] {
         unsafe {
             let data = raw::git_blob_rawcontent(&*self.raw) as *const u8;
@@ -34,6 +37,11 @@ impl<'repo> Blob<'repo> {
         }
     }
 
+    /// Get the size in bytes of the raw content of this blob.
+    pub fn rawsize(&self) -> u64 {
+        unsafe { raw::git_blob_rawsize(&*self.raw) as u64 }
+    }
+
     /// Casts this Blob to be usable as an `Object`
     pub fn as_object(&self) -> &Object<'repo> {
         unsafe {
@@ -153,6 +161,7 @@ mod tests {
 
         assert_eq!(blob.id(), id);
         assert_eq!(blob.content(), [5, 4, 6]);
+        assert_eq!(blob.rawsize(), 3);
         assert!(blob.is_binary());
 
         repo.find_object(id, None).unwrap().as_blob().unwrap();
@@ -168,9 +177,23 @@ mod tests {
         let id = repo.blob_path(&path).unwrap();
         let blob = repo.find_blob(id).unwrap();
         assert_eq!(blob.content(), [7, 8, 9]);
+        assert_eq!(blob.rawsize(), blob.content().len() as u64);
         blob.into_object();
     }
 
+    #[test]
+    fn path_outside_workdir() {
+        let td = TempDir::new("test").unwrap();
+        let outside = TempDir::new("test").unwrap();
+        let path = outside.path().join("foo");
+        File::create(&path).unwrap().write_all(b"a\r\nb\r\n").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        repo.config().unwrap().set_bool("core.autocrlf", true).unwrap();
+        let id = repo.blob_path(&path).unwrap();
+        let blob = repo.find_blob(id).unwrap();
+        assert_eq!(blob.content(), b"a\r\nb\r\n");
+    }
+
     #[test]
     fn stream() {
         let td = TempDir::new("test").unwrap();
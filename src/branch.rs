@@ -159,4 +159,42 @@ mod tests {
 
         b1.delete().unwrap();
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn filter_and_name_bytes() {
+        use std::ffi::OsStr;
+        use std::fs::File;
+        use std::io::prelude::*;
+        use std::os::unix::prelude::*;
+
+        let (_td, repo) = ::test::repo_init();
+        let target = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/origin/foo", target, false, "remote")
+            .unwrap();
+
+        // Branch names can't be created through the API from raw bytes, so
+        // write a loose ref with a Latin-1 name by hand.
+        let name = OsStr::from_bytes(b"caf\xe9");
+        let path = repo.path().join("refs/heads").join(name);
+        File::create(&path).unwrap()
+            .write_all(format!("{}\n", target).as_bytes()).unwrap();
+
+        let locals = repo.branches(Some(BranchType::Local)).unwrap()
+            .map(|b| b.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(locals.len(), 2);
+        assert!(locals.iter().all(|&(_, t)| t == BranchType::Local));
+        let latin1 = locals.iter().find(|&&(ref b, _)| {
+            b.name_bytes().unwrap() == b"caf\xe9"
+        }).unwrap();
+        assert_eq!(latin1.0.name().unwrap(), None);
+
+        let remotes = repo.branches(Some(BranchType::Remote)).unwrap()
+            .map(|b| b.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(remotes.len(), 1);
+        assert_eq!(remotes[0].0.name().unwrap(), Some("origin/foo"));
+        assert_eq!(remotes[0].1, BranchType::Remote);
+    }
 }
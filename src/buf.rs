@@ -3,7 +3,7 @@ use std::str;
 use std::ptr;
 use std::ops::{Deref, DerefMut};
 
-use raw;
+use {raw, Error};
 use util::Binding;
 
 /// A structure to wrap an intermediate buffer used by libgit2.
@@ -33,10 +33,26 @@ impl Buf {
         }
     }
 
+    /// Truncates this buffer to zero length, retaining its allocation.
+    ///
+    /// Functions which write into a caller-provided `Buf` append to its
+    /// existing contents, so a buffer should be cleared before it is reused.
+    pub fn clear(&mut self) {
+        unsafe {
+            raw::git_buf_set(&mut self.raw, ptr::null(), 0);
+        }
+    }
+
     /// Attempt to view this buffer as a string slice.
     ///
-    /// Returns `None` if the buffer is not valid utf-8.
-    pub fn as_str(&self) -> Option<&str> { str::from_utf8(&**self).ok() }
+    /// Returns an error containing the offset of the first invalid byte if
+    /// the buffer is not valid utf-8.
+    pub fn as_str(&self) -> Result<&str, Error> {
+        Ok(try!(str::from_utf8(&**self)))
+    }
+
+    /// View this buffer as a byte slice.
+    pub fn as_bytes(&self) -> &[u8] { &**self }
 }
 
 impl Deref for Buf {
@@ -71,3 +87,31 @@ impl Drop for Buf {
         unsafe { raw::git_buf_free(&mut self.raw) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use raw;
+    use util::Binding;
+    use Buf;
+
+    #[test]
+    fn as_str() {
+        let mut buf = Buf::new();
+        assert_eq!(t!(buf.as_str()), "");
+        let data = b"ok\xffno";
+        unsafe {
+            assert_eq!(raw::git_buf_set(buf.raw(), data.as_ptr() as *const _,
+                                        data.len() as _), 0);
+        }
+        assert_eq!(buf.as_bytes(), data);
+        let err = buf.as_str().unwrap_err();
+        assert!(err.message().contains("offset 2"));
+
+        buf.clear();
+        unsafe {
+            assert_eq!(raw::git_buf_set(buf.raw(), data.as_ptr() as *const _,
+                                        2), 0);
+        }
+        assert_eq!(t!(buf.as_str()), "ok");
+    }
+}
@@ -2,12 +2,12 @@
 
 use std::ffi::{CStr, CString};
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::ptr;
 use libc::{c_char, size_t, c_void, c_uint, c_int};
 
 use {raw, panic, Error, Repository, FetchOptions, IntoCString};
-use {CheckoutNotificationType, DiffFile, Remote};
+use {CheckoutNotificationType, DiffFile, Index, Remote};
 use util::{self, Binding};
 
 /// A builder struct which is used to build configuration for cloning a new git
@@ -35,6 +35,7 @@ pub struct CheckoutBuilder<'cb> {
     our_label: Option<CString>,
     ancestor_label: Option<CString>,
     target_dir: Option<CString>,
+    baseline_index: Option<&'cb Index>,
     paths: Vec<CString>,
     path_ptrs: Vec<*const c_char>,
     file_perm: Option<i32>,
@@ -44,6 +45,7 @@ pub struct CheckoutBuilder<'cb> {
     progress: Option<Box<Progress<'cb>>>,
     notify: Option<Box<Notify<'cb>>>,
     notify_flags: CheckoutNotificationType,
+    conflicts: Option<Vec<PathBuf>>,
 }
 
 /// Checkout progress notification callback.
@@ -276,6 +278,7 @@ impl<'cb> CheckoutBuilder<'cb> {
             path_ptrs: Vec::new(),
             paths: Vec::new(),
             target_dir: None,
+            baseline_index: None,
             ancestor_label: None,
             our_label: None,
             their_label: None,
@@ -283,6 +286,7 @@ impl<'cb> CheckoutBuilder<'cb> {
             progress: None,
             notify: None,
             notify_flags: CheckoutNotificationType::empty(),
+            conflicts: None,
         }
     }
 
@@ -476,6 +480,17 @@ impl<'cb> CheckoutBuilder<'cb> {
         self
     }
 
+    /// Set the index to use as the expected content of the working directory.
+    ///
+    /// By default the checkout compares against the tree of `HEAD` to decide
+    /// which files in the working directory have been modified; with a
+    /// baseline index that comparison is made against this index instead.
+    pub fn baseline_index(&mut self, index: &'cb Index)
+                          -> &mut CheckoutBuilder<'cb> {
+        self.baseline_index = Some(index);
+        self
+    }
+
     /// The name of the common ancestor side of conflicts
     pub fn ancestor_label(&mut self, label: &str) -> &mut CheckoutBuilder<'cb> {
         self.ancestor_label = Some(CString::new(label).unwrap());
@@ -513,6 +528,28 @@ impl<'cb> CheckoutBuilder<'cb> {
         self
     }
 
+    /// Indicate whether the paths of conflicting files should be collected
+    /// during checkout.
+    ///
+    /// When enabled, the paths which conflict with the checkout are available
+    /// through `conflicts` once it has finished, even if it failed because of
+    /// them. This works alongside any callback installed with `notify`.
+    ///
+    /// Defaults to false.
+    pub fn collect_conflicts(&mut self, collect: bool)
+                             -> &mut CheckoutBuilder<'cb> {
+        self.conflicts = if collect { Some(Vec::new()) } else { None };
+        self
+    }
+
+    /// Returns the conflicting paths found by the last checkout performed
+    /// with this builder.
+    ///
+    /// This is always empty unless `collect_conflicts` was enabled.
+    pub fn conflicts(&self) -> &[PathBuf] {
+        self.conflicts.as_ref().map(|c| &c[..]).unwrap_or(&[])
+    }
+
     /// Configure a raw checkout options based on this configuration.
     ///
     /// This method is unsafe as there is no guarantee that this structure will
@@ -531,6 +568,9 @@ impl<'cb> CheckoutBuilder<'cb> {
         if let Some(ref c) = self.target_dir {
             opts.target_directory = c.as_ptr();
         }
+        if let Some(index) = self.baseline_index {
+            opts.baseline_index = index.raw();
+        }
         if let Some(ref c) = self.ancestor_label {
             opts.ancestor_label = c.as_ptr();
         }
@@ -545,11 +585,18 @@ impl<'cb> CheckoutBuilder<'cb> {
             opts.progress_cb = Some(f);
             opts.progress_payload = self as *mut _ as *mut _;
         }
-        if self.notify.is_some() {
+        if let Some(ref mut conflicts) = self.conflicts {
+            conflicts.clear();
+        }
+        if self.notify.is_some() || self.conflicts.is_some() {
+            let mut flags = self.notify_flags;
+            if self.conflicts.is_some() {
+                flags |= CheckoutNotificationType::CONFLICT;
+            }
             let f: raw::git_checkout_notify_cb = notify_cb;
             opts.notify_cb = Some(f);
             opts.notify_payload = self as *mut _ as *mut _;
-            opts.notify_flags = self.notify_flags.bits() as c_uint;
+            opts.notify_flags = flags.bits() as c_uint;
         }
         opts.checkout_strategy = self.checkout_opts as c_uint;
     }
@@ -583,10 +630,6 @@ extern fn notify_cb(why: raw::git_checkout_notify_t,
     // pack callback etc
     panic::wrap(|| unsafe {
         let payload = &mut *(data as *mut CheckoutBuilder);
-        let callback = match payload.notify {
-            Some(ref mut c) => c,
-            None => return 0,
-        };
         let path = if path.is_null() {
             None
         } else {
@@ -594,6 +637,18 @@ extern fn notify_cb(why: raw::git_checkout_notify_t,
         };
 
         let why = CheckoutNotificationType::from_bits_truncate(why as u32);
+        if why.contains(CheckoutNotificationType::CONFLICT) {
+            if let (Some(c), Some(path)) = (payload.conflicts.as_mut(), path) {
+                c.push(path.to_path_buf());
+            }
+        }
+        if !payload.notify_flags.intersects(why) {
+            return 0
+        }
+        let callback = match payload.notify {
+            Some(ref mut c) => c,
+            None => return 0,
+        };
         let keep_going = callback(why,
                                   path,
                                   DiffFile::from_raw(baseline),
@@ -605,10 +660,11 @@ extern fn notify_cb(why: raw::git_checkout_notify_t,
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
+    use std::fs::{self, File};
+    use std::io::prelude::*;
     use std::path::Path;
     use tempdir::TempDir;
-    use super::RepoBuilder;
+    use super::{CheckoutBuilder, RepoBuilder};
     use Repository;
 
     #[test]
@@ -635,4 +691,154 @@ mod tests {
                                   .clone(&url, &dst).is_err());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn clone_local_no_links() {
+        use std::os::unix::prelude::*;
+        use super::CloneLocal;
+
+        let (td, repo) = ::test::repo_init();
+        let id = t!(repo.blob(b"foo")).to_string();
+        let object = Path::new("objects").join(&id[..2]).join(&id[2..]);
+        let src = td.path().join(".git").join(&object);
+        assert_eq!(t!(fs::metadata(&src)).nlink(), 1);
+
+        let td2 = TempDir::new("test").unwrap();
+        let dst = td2.path().join("foo");
+        let cloned = t!(RepoBuilder::new().clone_local(CloneLocal::NoLinks)
+                                          .clone(td.path().to_str().unwrap(),
+                                                 &dst));
+        let copy = cloned.path().join(&object);
+        assert_eq!(t!(fs::metadata(&copy)).nlink(), 1);
+        assert_eq!(t!(fs::metadata(&src)).nlink(), 1);
+        assert_eq!(t!(cloned.find_blob(t!(id.parse()))).content(), b"foo");
+    }
+
+    #[test]
+    fn checkout_paths() {
+        let (td, repo) = ::test::repo_init();
+        t!(t!(File::create(&td.path().join("a"))).write_all(b"a"));
+        t!(t!(File::create(&td.path().join("b"))).write_all(b"b"));
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("a")).unwrap();
+            index.add_path(Path::new("b")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            let sig = repo.signature().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "two files",
+                        &tree, &[&head]).unwrap();
+        }
+
+        t!(t!(File::create(&td.path().join("a"))).write_all(b"changed"));
+        t!(t!(File::create(&td.path().join("b"))).write_all(b"changed"));
+        repo.checkout_head(Some(CheckoutBuilder::new().force().path("a")))
+            .unwrap();
+        let mut contents = String::new();
+        t!(t!(File::open(&td.path().join("a"))).read_to_string(&mut contents));
+        assert_eq!(contents, "a");
+        contents.clear();
+        t!(t!(File::open(&td.path().join("b"))).read_to_string(&mut contents));
+        assert_eq!(contents, "changed");
+    }
+
+    #[test]
+    fn collect_conflicts() {
+        let (td, repo) = ::test::repo_init();
+        let path = td.path().join("a");
+        t!(t!(File::create(&path)).write_all(b"one\n"));
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = repo.signature().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "one", &tree, &[&head]).unwrap();
+        t!(t!(File::create(&path)).write_all(b"dirty\n"));
+
+        let initial = head.tree().unwrap();
+        let mut notified = 0;
+        {
+            let mut opts = CheckoutBuilder::new();
+            opts.collect_conflicts(true).notify(|_, _, _, _, _| {
+                notified += 1;
+                true
+            });
+            assert!(repo.checkout_tree(initial.as_object(),
+                                       Some(&mut opts)).is_err());
+            assert_eq!(opts.conflicts(), [Path::new("a")]);
+        }
+        // The notify callback didn't ask for conflict notifications.
+        assert_eq!(notified, 0);
+
+        let mut opts = CheckoutBuilder::new();
+        assert!(repo.checkout_tree(initial.as_object(),
+                                   Some(&mut opts)).is_err());
+        assert!(opts.conflicts().is_empty());
+    }
+
+    #[test]
+    fn conflict_style() {
+        let (td, repo) = ::test::repo_init();
+        let tree = |data: &[u8]| ::test::single_file_tree(&repo, data);
+        let ancestor = tree(b"a\nbase\nc\n");
+        let ours = tree(b"a\nours\nc\n");
+        let theirs = tree(b"a\ntheirs\nc\n");
+        let contents = |opts: &mut CheckoutBuilder| {
+            let mut index = repo.merge_trees(&ancestor, &ours, &theirs, None)
+                                .unwrap();
+            repo.checkout_index(Some(&mut index), Some(opts.force())).unwrap();
+            let mut contents = String::new();
+            t!(t!(File::open(td.path().join("file")))
+                   .read_to_string(&mut contents));
+            contents
+        };
+
+        let merge = contents(CheckoutBuilder::new().conflict_style_merge(true));
+        assert!(merge.contains("<<<<<<<"));
+        assert!(!merge.contains("|||||||"));
+        assert!(!merge.contains("base"));
+
+        let diff3 = contents(CheckoutBuilder::new().conflict_style_diff3(true));
+        assert!(diff3.contains("<<<<<<<"));
+        assert!(diff3.contains("|||||||"));
+        assert!(diff3.contains("\nbase\n"));
+    }
+
+    #[test]
+    fn checkout_baseline_index() {
+        let (td, repo) = ::test::repo_init();
+        let path = td.path().join("a");
+        t!(t!(File::create(&path)).write_all(b"one\n"));
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("a")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            let sig = repo.signature().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "one", &tree,
+                        &[&head]).unwrap();
+        }
+
+        t!(t!(File::create(&path)).write_all(b"two two\n"));
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a")).unwrap();
+        index.write().unwrap();
+
+        // Compared against HEAD the working directory is modified, so a safe
+        // checkout refuses to overwrite it.
+        assert!(repo.checkout_head(Some(&mut CheckoutBuilder::new())).is_err());
+        let mut contents = String::new();
+        t!(t!(File::open(&path)).read_to_string(&mut contents));
+        assert_eq!(contents, "two two\n");
+
+        // Compared against the index it is not, so the file is updated.
+        repo.checkout_head(Some(CheckoutBuilder::new()
+                                    .baseline_index(&index))).unwrap();
+        contents.clear();
+        t!(t!(File::open(&path)).read_to_string(&mut contents));
+        assert_eq!(contents, "one\n");
+    }
 }
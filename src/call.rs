@@ -56,6 +56,8 @@ mod impls {
 
     use {raw, ConfigLevel, ResetType, ObjectType, BranchType, Direction};
     use {DiffFormat, FileFavor, SubmoduleIgnore, AutotagOption, FetchPrune};
+    use SubmoduleUpdate;
+    use {AttrCheckOrder, DiffLineType, FilterMode};
     use call::Convert;
 
     impl<T: Copy> Convert<T> for T {
@@ -193,6 +195,19 @@ mod impls {
         }
     }
 
+    impl Convert<raw::git_submodule_update_t> for SubmoduleUpdate {
+        fn convert(&self) -> raw::git_submodule_update_t {
+            match *self {
+                SubmoduleUpdate::Checkout =>
+                    raw::GIT_SUBMODULE_UPDATE_CHECKOUT,
+                SubmoduleUpdate::Rebase => raw::GIT_SUBMODULE_UPDATE_REBASE,
+                SubmoduleUpdate::Merge => raw::GIT_SUBMODULE_UPDATE_MERGE,
+                SubmoduleUpdate::None => raw::GIT_SUBMODULE_UPDATE_NONE,
+                SubmoduleUpdate::Default => raw::GIT_SUBMODULE_UPDATE_DEFAULT,
+            }
+        }
+    }
+
     impl Convert<raw::git_remote_autotag_option_t> for AutotagOption {
         fn convert(&self) -> raw::git_remote_autotag_option_t {
             match *self {
@@ -214,4 +229,42 @@ mod impls {
             }
         }
     }
+
+    impl Convert<raw::git_filter_mode_t> for FilterMode {
+        fn convert(&self) -> raw::git_filter_mode_t {
+            match *self {
+                FilterMode::ToWorktree => raw::GIT_FILTER_TO_WORKTREE,
+                FilterMode::ToOdb => raw::GIT_FILTER_TO_ODB,
+            }
+        }
+    }
+
+    impl Convert<raw::git_diff_line_t> for DiffLineType {
+        fn convert(&self) -> raw::git_diff_line_t {
+            match *self {
+                DiffLineType::Context => raw::GIT_DIFF_LINE_CONTEXT,
+                DiffLineType::Addition => raw::GIT_DIFF_LINE_ADDITION,
+                DiffLineType::Deletion => raw::GIT_DIFF_LINE_DELETION,
+                DiffLineType::ContextEOFNL => raw::GIT_DIFF_LINE_CONTEXT_EOFNL,
+                DiffLineType::AddEOFNL => raw::GIT_DIFF_LINE_ADD_EOFNL,
+                DiffLineType::DelEOFNL => raw::GIT_DIFF_LINE_DEL_EOFNL,
+                DiffLineType::FileHeader => raw::GIT_DIFF_LINE_FILE_HDR,
+                DiffLineType::HunkHeader => raw::GIT_DIFF_LINE_HUNK_HDR,
+                DiffLineType::Binary => raw::GIT_DIFF_LINE_BINARY,
+                DiffLineType::Other(c) => c as u8 as raw::git_diff_line_t,
+            }
+        }
+    }
+
+    impl Convert<u32> for AttrCheckOrder {
+        fn convert(&self) -> u32 {
+            match *self {
+                AttrCheckOrder::FileThenIndex =>
+                    raw::GIT_ATTR_CHECK_FILE_THEN_INDEX,
+                AttrCheckOrder::IndexThenFile =>
+                    raw::GIT_ATTR_CHECK_INDEX_THEN_FILE,
+                AttrCheckOrder::IndexOnly => raw::GIT_ATTR_CHECK_INDEX_ONLY,
+            }
+        }
+    }
 }
@@ -133,6 +133,29 @@ impl<'repo> Commit<'repo> {
         unsafe { ::opt_bytes(self, raw::git_commit_summary(self.raw)) }
     }
 
+    /// Get the long "body" of the git commit message.
+    ///
+    /// The returned message is the body of the commit, comprising everything
+    /// but the first paragraph of the message. Leading and trailing
+    /// whitespace is trimmed.
+    ///
+    /// `None` may be returned if the message has no body, if an error occurs
+    /// or if the body is not valid utf-8.
+    pub fn body(&self) -> Option<&str> {
+        self.body_bytes().and_then(|s| str::from_utf8(s).ok())
+    }
+
+    /// Get the long "body" of the git commit message.
+    ///
+    /// The returned message is the body of the commit, comprising everything
+    /// but the first paragraph of the message. Leading and trailing
+    /// whitespace is trimmed.
+    ///
+    /// `None` may be returned if the message has no body or an error occurs.
+    pub fn body_bytes(&self) -> Option<&[u8]> {
+        unsafe { ::opt_bytes(self, raw::git_commit_body(self.raw)) }
+    }
+
     /// Get the commit time (i.e. committer time) of a commit.
     ///
     /// The first element of the tuple is the time, in seconds, since the epoch.
@@ -151,10 +174,22 @@ impl<'repo> Commit<'repo> {
         Parents { range: 0..max, commit: self }
     }
 
-    /// Creates a new iterator over the parents of this commit.
+    /// Creates a new iterator over the ids of the parents of this commit.
+    ///
+    /// Unlike `parents`, this does not load the parent commits from the ODB.
     pub fn parent_ids(&self) -> ParentIds {
-        let max = unsafe { raw::git_commit_parentcount(&*self.raw) as usize };
-        ParentIds { range: 0..max, commit: self }
+        ParentIds { range: 0..self.parent_count(), commit: self }
+    }
+
+    /// Get the number of parents of this commit.
+    pub fn parent_count(&self) -> usize {
+        unsafe { raw::git_commit_parentcount(&*self.raw) as usize }
+    }
+
+    /// Returns whether this commit is a merge, that is whether it has more
+    /// than one parent.
+    pub fn is_merge(&self) -> bool {
+        self.parent_count() > 1
     }
 
     /// Get the author of this commit.
@@ -235,6 +270,20 @@ impl<'repo> Commit<'repo> {
         }
     }
 
+    /// Get the commit object that is the `n`th generation ancestor of this
+    /// commit, following only the first parents.
+    ///
+    /// An `n` of 0 returns this commit itself. An error with the code
+    /// `ErrorCode::NotFound` is returned if the history is not that deep.
+    pub fn nth_gen_ancestor(&self, n: u32) -> Result<Commit<'repo>, Error> {
+        unsafe {
+            let mut raw = ptr::null_mut();
+            try_call!(raw::git_commit_nth_gen_ancestor(&mut raw, &*self.raw,
+                                                       n as libc::c_uint));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
     /// Casts this Commit to be usable as an `Object`
     pub fn as_object(&self) -> &Object<'repo> {
         unsafe {
@@ -355,5 +404,70 @@ mod tests {
         repo.find_object(target, None).unwrap().as_commit().unwrap();
         repo.find_object(target, None).unwrap().into_commit().ok().unwrap();
     }
-}
 
+    #[test]
+    fn summary_and_body() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("initial"));
+        assert_eq!(head.body(), None);
+
+        let tree = head.tree().unwrap();
+        let message = "Fix the frobnicator\n\n\
+                       It was broken.\nNow it is not.\n";
+        let id = repo.commit(None, &sig, &sig, message, &tree,
+                             &[&head]).unwrap();
+        let commit = repo.find_commit(id).unwrap();
+        assert_eq!(commit.summary(), Some("Fix the frobnicator"));
+        assert_eq!(commit.body(), Some("It was broken.\nNow it is not."));
+        assert_eq!(commit.body_bytes(),
+                   Some(&b"It was broken.\nNow it is not."[..]));
+    }
+
+    #[test]
+    fn merge_parents() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(root.parent_count(), 0);
+        assert!(!root.is_merge());
+
+        let tree = root.tree().unwrap();
+        let a = repo.commit(None, &sig, &sig, "a", &tree, &[&root]).unwrap();
+        let b = repo.commit(None, &sig, &sig, "b", &tree, &[&root]).unwrap();
+        let a = repo.find_commit(a).unwrap();
+        let b = repo.find_commit(b).unwrap();
+        assert_eq!(a.parent_count(), 1);
+        assert!(!a.is_merge());
+
+        let merge = repo.commit(None, &sig, &sig, "merge", &tree,
+                                &[&a, &b]).unwrap();
+        let merge = repo.find_commit(merge).unwrap();
+        assert!(merge.is_merge());
+        assert_eq!(merge.parent_count(), 2);
+        assert_eq!(merge.parent_ids().collect::<Vec<_>>(), [a.id(), b.id()]);
+        assert_eq!(merge.parent_ids().len(), 2);
+    }
+
+    #[test]
+    fn nth_gen_ancestor() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let mut commit = repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = commit.tree().unwrap();
+        let mut ids = vec![commit.id()];
+        for i in 0..4 {
+            let id = repo.commit(None, &sig, &sig, &format!("c{}", i), &tree,
+                                 &[&commit]).unwrap();
+            commit = repo.find_commit(id).unwrap();
+            ids.push(id);
+        }
+
+        assert_eq!(commit.nth_gen_ancestor(0).unwrap().id(), ids[4]);
+        assert_eq!(commit.nth_gen_ancestor(3).unwrap().id(), ids[1]);
+        assert_eq!(commit.nth_gen_ancestor(4).unwrap().id(), ids[0]);
+        let err = commit.nth_gen_ancestor(5).err().unwrap();
+        assert_eq!(err.code(), ::ErrorCode::NotFound);
+    }
+}
@@ -226,7 +226,11 @@ impl Config {
         })
     }
 
-    /// Get the value of a path config variable as an owned .
+    /// Get the value of a path config variable as an owned `PathBuf`.
+    ///
+    /// A leading `~/` or `~user/` in the value is expanded to the relevant
+    /// home directory, as git does for path-typed variables such as
+    /// `core.excludesFile`.
     pub fn get_path(&self, name: &str) -> Result<PathBuf, Error> {
         let ret = Buf::new();
         let name = try!(CString::new(name));
@@ -249,7 +253,9 @@ impl Config {
     /// Iterate over all the config variables
     ///
     /// If `glob` is `Some`, then the iterator will only iterate over all
-    /// variables whose name matches the pattern.
+    /// variables whose name matches the pattern. Despite its name the pattern
+    /// is a regular expression, matched anywhere in the variable name unless
+    /// anchored, such as `^remote\..*\.url$`.
     ///
     /// # Example
     ///
@@ -563,6 +569,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn get_path() {
+        let td = TempDir::new("test").unwrap();
+        let path = td.path().join("foo");
+        File::create(&path).unwrap();
+
+        let mut cfg = Config::open(&path).unwrap();
+        cfg.set_str("core.excludesfile", "~/ignore").unwrap();
+        let abs = td.path().join("bar");
+        cfg.set_str("foo.bar", abs.to_str().unwrap()).unwrap();
+
+        let expanded = cfg.get_path("core.excludesfile").unwrap();
+        assert!(expanded.is_absolute());
+        assert!(expanded.ends_with("ignore"));
+        assert!(!expanded.starts_with("~"));
+        assert_eq!(cfg.get_path("foo.bar").unwrap(), abs);
+        assert!(cfg.get_path("foo.baz").is_err());
+    }
+
+    #[test]
+    fn entries_pattern() {
+        let td = TempDir::new("test").unwrap();
+        let path = td.path().join("foo");
+        File::create(&path).unwrap();
+
+        let mut cfg = Config::open(&path).unwrap();
+        cfg.set_str("remote.origin.url", "https://example.com/a").unwrap();
+        cfg.set_str("remote.origin.fetch", "+refs/*:refs/*").unwrap();
+        cfg.set_str("remote.upstream.url", "https://example.com/b").unwrap();
+        cfg.set_str("foo.url", "https://example.com/c").unwrap();
+
+        let mut names: Vec<String> = cfg.entries(Some(r"^remote\..*\.url$"))
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.unwrap().name().unwrap().into())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["remote.origin.url", "remote.upstream.url"]);
+        assert!(cfg.entries(Some("[")).is_err());
+    }
+
     #[test]
     fn multivar() {
         let td = TempDir::new("test").unwrap();
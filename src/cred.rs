@@ -108,8 +108,10 @@ impl Cred {
 
     /// Create a credential to specify a username.
     ///
-    /// THis is used with ssh authentication to query for the username if non is
-    /// specified in the url.
+    /// This is used with ssh authentication to query for the username if none
+    /// is specified in the url. Credential callbacks should return this when
+    /// the allowed types contain `CredentialType::USERNAME`, after which the
+    /// transport will invoke the callback again for the actual credentials.
     pub fn username(username: &str) -> Result<Cred, Error> {
         ::init();
         let username = try!(CString::new(username));
@@ -398,7 +400,7 @@ mod test {
     use std::path::Path;
     use tempdir::TempDir;
 
-    use {Cred, Config, CredentialHelper, ConfigLevel};
+    use {raw, Cred, Config, CredentialHelper, ConfigLevel};
 
     macro_rules! cfg( ($($k:expr => $v:expr),*) => ({
         let td = TempDir::new("git2-rs").unwrap();
@@ -413,6 +415,14 @@ mod test {
         Cred::default().unwrap();
     }
 
+    #[test]
+    fn username() {
+        let cred = Cred::username("git").unwrap();
+        assert_eq!(cred.credtype(), raw::GIT_CREDTYPE_USERNAME);
+        assert!(cred.has_username());
+        assert!(Cred::username("a\0b").is_err());
+    }
+
     #[test]
     fn credential_helper1() {
         let cfg = cfg! {
@@ -504,6 +514,21 @@ echo username=c
                 .execute().is_none());
     }
 
+    #[test]
+    fn credential_helper_cred() {
+        let cfg = cfg! {
+            "credential.helper" => "!f() { echo password=b; }; f"
+        };
+        let cred = Cred::credential_helper(&cfg, "https://example.com/foo",
+                                           Some("a")).unwrap();
+        assert_eq!(cred.credtype(), raw::GIT_CREDTYPE_USERPASS_PLAINTEXT);
+        assert!(cred.has_username());
+
+        let cfg = cfg! {};
+        assert!(Cred::credential_helper(&cfg, "https://example.com/foo",
+                                        None).is_err());
+    }
+
     #[cfg(unix)]
     fn chmod(path: &Path) {
         use std::os::unix::prelude::*;
@@ -116,7 +116,9 @@ impl DescribeOptions {
         opts
     }
 
-    #[allow(missing_docs)]
+    /// Sets the number of candidate tags to consider, defaulting to 10.
+    ///
+    /// This behaves like the `--candidates` option to git-describe.
     pub fn max_candidates_tags(&mut self, max: u32) -> &mut Self {
         self.raw.max_candidates_tags = max as c_uint;
         self
@@ -153,7 +155,9 @@ impl DescribeOptions {
         self
     }
 
-    #[allow(missing_docs)]
+    /// Only consider tags matching the given glob pattern.
+    ///
+    /// This behaves like the `--match` option to git-describe.
     pub fn pattern(&mut self, pattern: &str) -> &mut Self {
         self.pattern = CString::new(pattern).unwrap();
         self.raw.pattern = self.pattern.as_ptr();
@@ -196,4 +200,47 @@ mod tests {
         let d = t!(obj.describe(&DescribeOptions::new()));
         assert_eq!(t!(d.format(None)), "foo");
     }
+
+    #[test]
+    fn pattern() {
+        let (_td, repo) = ::test::repo_init();
+        let head = t!(repo.head()).target().unwrap();
+        let obj = t!(repo.find_object(head, None));
+        let sig = t!(repo.signature());
+        t!(repo.tag("v1.0", &obj, &sig, "release", false));
+        t!(repo.tag("nightly-x", &obj, &sig, "nightly", false));
+
+        let d = t!(repo.describe(DescribeOptions::new().pattern("v*")));
+        assert_eq!(t!(d.format(None)), "v1.0");
+        let d = t!(repo.describe(DescribeOptions::new().pattern("nightly-*")));
+        assert_eq!(t!(d.format(None)), "nightly-x");
+        assert!(repo.describe(DescribeOptions::new().pattern("x*")).is_err());
+    }
+
+    #[test]
+    fn older_commit() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let first = t!(t!(repo.head()).peel_to_commit());
+        t!(repo.tag("v1", first.as_object(), &sig, "v1", false));
+
+        let tree = t!(first.tree());
+        let mut parent = first;
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            let id = t!(repo.commit(Some("HEAD"), &sig, &sig, "next", &tree,
+                                    &[&parent]));
+            parent = t!(repo.find_commit(id));
+            ids.push(id);
+        }
+        t!(repo.tag("v2", parent.as_object(), &sig, "v2", false));
+
+        let old = t!(repo.find_object(ids[1], None));
+        let d = t!(old.describe(&DescribeOptions::new()));
+        let expected = format!("v1-2-g{}", &ids[1].to_string()[..7]);
+        assert_eq!(t!(d.format(None)), expected);
+
+        let d = t!(repo.describe(&DescribeOptions::new()));
+        assert_eq!(t!(d.format(None)), "v2");
+    }
 }
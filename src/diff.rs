@@ -7,8 +7,9 @@ use std::ptr;
 use std::slice;
 use libc::{c_char, size_t, c_void, c_int};
 
-use {raw, panic, Buf, Delta, Oid, Repository, Error, DiffFormat};
-use {DiffStatsFormat, IntoCString};
+use {raw, panic, Buf, Commit, Delta, Oid, Patch, Repository, Error};
+use DiffFormat;
+use {DiffLineType, DiffStatsFormat, IntoCString};
 use util::{self, Binding};
 
 /// The diff object that contains all individual file deltas.
@@ -45,6 +46,7 @@ pub struct DiffOptions {
     pathspec_ptrs: Vec<*const c_char>,
     old_prefix: Option<CString>,
     new_prefix: Option<CString>,
+    progress: Option<Box<ProgressCb>>,
     raw: raw::git_diff_options,
 }
 
@@ -53,6 +55,13 @@ pub struct DiffFindOptions {
     raw: raw::git_diff_find_options,
 }
 
+/// Control behavior of formatting a diff as an email
+pub struct DiffFormatEmailOptions {
+    raw: raw::git_diff_format_email_options,
+    subject_prefix: Option<String>,
+    reroll_number: Option<usize>,
+}
+
 /// An iterator over the diffs in a delta
 pub struct Deltas<'diff> {
     range: Range<usize>,
@@ -103,6 +112,7 @@ pub enum DiffBinaryKind {
 }
 
 type PrintCb<'a> = FnMut(DiffDelta, Option<DiffHunk>, DiffLine) -> bool + 'a;
+type ProgressCb = FnMut(Option<&Path>, Option<&Path>) -> bool;
 
 pub type FileCb<'a> = FnMut(DiffDelta, f32) -> bool + 'a;
 pub type BinaryCb<'a> = FnMut(DiffDelta, DiffBinary) -> bool + 'a;
@@ -116,6 +126,27 @@ struct ForeachCallbacks<'a, 'b: 'a, 'c, 'd: 'c, 'e, 'f: 'e, 'g, 'h: 'g> {
     line: Option<&'g mut LineCb<'h>>,
 }
 
+impl Diff<'static> {
+    /// Read the contents of a git patch file into a `Diff` object.
+    ///
+    /// The diff object produced is similar to the one that would be produced
+    /// if you actually produced it computationally by comparing two trees,
+    /// however there may be subtle differences. For example, a patch file
+    /// likely contains abbreviated object IDs, so the object IDs parsed by
+    /// this function will also be abbreviated.
+    ///
+    /// An error is returned if the buffer is not a well-formed patch.
+    pub fn from_buffer(content: &[u8]) -> Result<Diff<'static>, Error> {
+        ::init();
+        let mut ret = ptr::null_mut();
+        let ptr = content.as_ptr() as *const c_char;
+        unsafe {
+            try_call!(raw::git_diff_from_buffer(&mut ret, ptr, content.len()));
+            Ok(Binding::from_raw(ret))
+        }
+    }
+}
+
 impl<'repo> Diff<'repo> {
     /// Merge one diff into another.
     ///
@@ -149,6 +180,36 @@ impl<'repo> Diff<'repo> {
         unsafe { raw::git_diff_is_sorted_icase(&*self.raw) == 1 }
     }
 
+    /// Compute the number of added and deleted lines for each delta, in the
+    /// manner of `git diff --numstat`.
+    ///
+    /// Each element is a tuple of the line counts and path of one delta, in
+    /// the order of the deltas in this diff. The line counts are the number
+    /// of additions and deletions, or `None` for binary files, which git
+    /// prints as `-`. The path is that of the new file, or of the old file if
+    /// it was deleted.
+    pub fn numstat(&self)
+                   -> Result<Vec<(Option<(usize, usize)>, String)>, Error> {
+        let mut ret = Vec::new();
+        for (i, delta) in self.deltas().enumerate() {
+            let path = delta.new_file().path_bytes()
+                            .or_else(|| delta.old_file().path_bytes())
+                            .map(|p| String::from_utf8_lossy(p).into_owned())
+                            .unwrap_or_default();
+            let stats = match try!(Patch::from_diff(self, i)) {
+                Some(ref patch) if patch.delta().is_binary() => None,
+                Some(patch) => {
+                    let (_, additions, deletions) = try!(patch.line_stats());
+                    Some((additions, deletions))
+                }
+                None if delta.is_binary() => None,
+                None => Some((0, 0)),
+            };
+            ret.push((stats, path));
+        }
+        Ok(ret)
+    }
+
     /// Iterate over a diff generating formatted text output.
     ///
     /// Returning `false` from the callback will terminate the iteration and
@@ -166,6 +227,26 @@ impl<'repo> Diff<'repo> {
         }
     }
 
+    /// Produce the complete formatted text output of this diff into a Buf.
+    pub fn to_buf(&self, format: DiffFormat) -> Result<Buf, Error> {
+        let mut buf = Buf::new();
+        try!(self.to_buf_into(format, &mut buf));
+        Ok(buf)
+    }
+
+    /// Append the complete formatted text output of this diff to an existing
+    /// Buf.
+    ///
+    /// The output is appended to any contents already in `buf`, so call
+    /// `Buf::clear` first to reuse a buffer across calls.
+    pub fn to_buf_into(&self, format: DiffFormat, buf: &mut Buf)
+                       -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_diff_to_buf(buf.raw(), self.raw, format));
+        }
+        Ok(())
+    }
+
     /// Loop over all deltas in a diff issuing callbacks.
     ///
     /// Returning `false` from any callback will terminate the iteration and
@@ -227,7 +308,65 @@ impl<'repo> Diff<'repo> {
         Ok(())
     }
 
-    // TODO: num_deltas_of_type, format_email, find_similar
+    /// Create an e-mail ready patch from a diff.
+    ///
+    /// The subject, author and date are taken from `commit`, and the subject
+    /// is prefixed with a `[PATCH patch_no/total_patches]` marker unless
+    /// disabled through `opts`. A `total_patches` of 1 produces a plain
+    /// `[PATCH]` marker.
+    pub fn format_email(&self,
+                        patch_no: usize,
+                        total_patches: usize,
+                        commit: &Commit,
+                        opts: Option<&DiffFormatEmailOptions>)
+                        -> Result<Buf, Error> {
+        let default = DiffFormatEmailOptions::new();
+        let opts = opts.unwrap_or(&default);
+        let mut raw = opts.raw;
+        let id = commit.id();
+        let author = commit.author();
+        let mut summary = commit.summary_bytes().unwrap_or(b"").to_vec();
+        let marker = raw::GIT_DIFF_FORMAT_EMAIL_EXCLUDE_SUBJECT_PATCH_MARKER;
+        if raw.flags & marker == 0 &&
+           (opts.subject_prefix.is_some() || opts.reroll_number.is_some()) {
+            // libgit2 can only write a plain `[PATCH n/m]` marker, so build
+            // a custom one into the summary instead. That also skips the
+            // range check libgit2 does on the patch number, so do it here.
+            if patch_no == 0 || patch_no > total_patches {
+                return Err(Error::from_str(&format!(
+                    "patch {} out of range, max {}", patch_no, total_patches)))
+            }
+            let mut subject = format!("[{}",
+                                      opts.subject_prefix.as_ref()
+                                          .map_or("PATCH", |s| &s[..]));
+            if let Some(n) = opts.reroll_number {
+                subject.push_str(&format!(" v{}", n));
+            }
+            if total_patches > 1 {
+                subject.push_str(&format!(" {}/{}", patch_no, total_patches));
+            }
+            subject.push_str("] ");
+            let mut subject = subject.into_bytes();
+            subject.extend(summary);
+            summary = subject;
+            raw.flags |= marker;
+        }
+        let summary = try!(CString::new(summary));
+        let body = try!(::opt_cstr(commit.body_bytes()));
+        raw.patch_no = patch_no as size_t;
+        raw.total_patches = total_patches as size_t;
+        raw.id = id.raw();
+        raw.summary = summary.as_ptr();
+        raw.body = body.as_ref().map_or(ptr::null(), |s| s.as_ptr());
+        raw.author = author.raw();
+        let buf = Buf::new();
+        unsafe {
+            try_call!(raw::git_diff_format_email(buf.raw(), self.raw, &raw));
+        }
+        Ok(buf)
+    }
+
+    // TODO: num_deltas_of_type, find_similar
 }
 
 pub extern fn print_cb(delta: *const raw::git_diff_delta,
@@ -317,6 +456,24 @@ extern fn line_cb_c(delta: *const raw::git_diff_delta,
     }
 }
 
+extern fn progress_cb_c(diff: *const raw::git_diff,
+                        old_path: *const c_char,
+                        new_path: *const c_char,
+                        data: *mut c_void) -> c_int {
+    unsafe {
+        let r = panic::wrap(|| {
+            let opts = &mut *(data as *mut DiffOptions);
+            let cb = match opts.progress {
+                Some(ref mut cb) => cb,
+                None => return true,
+            };
+            let old_path = ::opt_bytes(&diff, old_path).map(util::bytes2path);
+            let new_path = ::opt_bytes(&diff, new_path).map(util::bytes2path);
+            cb(old_path, new_path)
+        });
+        if r == Some(true) {0} else {-1}
+    }
+}
 
 impl<'repo> Binding for Diff<'repo> {
     type Raw = *mut raw::git_diff;
@@ -346,6 +503,10 @@ impl<'a> DiffDelta<'a> {
         unsafe { (*self.raw).nfiles }
     }
 
+    fn is_binary(&self) -> bool {
+        unsafe { (*self.raw).flags & raw::GIT_DIFF_FLAG_BINARY != 0 }
+    }
+
     /// Returns the status of this entry
     ///
     /// For more information, see `Delta`'s documentation
@@ -451,6 +612,7 @@ impl DiffOptions {
             raw: unsafe { mem::zeroed() },
             old_prefix: None,
             new_prefix: None,
+            progress: None,
         };
         assert_eq!(unsafe {
             raw::git_diff_init_options(&mut opts.raw, 1)
@@ -580,7 +742,7 @@ impl DiffOptions {
 
     /// Treat all files as binary, disabling text diffs
     pub fn force_binary(&mut self, force: bool) -> &mut DiffOptions {
-        self.flag(raw::GIT_DIFF_FORCE_TEXT, force)
+        self.flag(raw::GIT_DIFF_FORCE_BINARY, force)
     }
 
     /// Ignore all whitespace
@@ -627,6 +789,9 @@ impl DiffOptions {
 
     /// Include the necessary deflate/delta information so that `git-apply` can
     /// apply given diff information to binary files.
+    ///
+    /// Without this, patches for binary files only contain a "Binary files
+    /// differ" line, as with `git diff` when `--binary` is not passed.
     pub fn show_binary(&mut self, show: bool) -> &mut DiffOptions {
         self.flag(raw::GIT_DIFF_SHOW_BINARY, show)
     }
@@ -698,11 +863,28 @@ impl DiffOptions {
         self
     }
 
+    /// Set a callback to receive progress while the diff is being generated.
+    ///
+    /// The callback is invoked for each pair of items compared, with the old
+    /// and new paths of that pair; one side is `None` when the item exists on
+    /// only one side of the diff. Returning `false` aborts the diff, which
+    /// then returns an error.
+    pub fn progress<F>(&mut self, cb: F) -> &mut DiffOptions
+        where F: FnMut(Option<&Path>, Option<&Path>) -> bool + 'static
+    {
+        self.progress = Some(Box::new(cb) as Box<ProgressCb>);
+        self
+    }
+
     /// Acquire a pointer to the underlying raw options.
     ///
     /// This function is unsafe as the pointer is only valid so long as this
     /// structure is not moved, modified, or used elsewhere.
     pub unsafe fn raw(&mut self) -> *const raw::git_diff_options {
+        if self.progress.is_some() {
+            self.raw.progress_cb = progress_cb_c;
+            self.raw.payload = self as *mut _ as *mut _;
+        }
         self.raw.old_prefix = self.old_prefix.as_ref().map(|s| s.as_ptr())
                                   .unwrap_or(ptr::null());
         self.raw.new_prefix = self.new_prefix.as_ref().map(|s| s.as_ptr())
@@ -789,6 +971,13 @@ impl<'a> DiffLine<'a> {
             _ => ' ',
         }
     }
+
+    /// The type of this line, as an enum rather than the sigil returned by
+    /// `origin`.
+    pub fn origin_value(&self) -> DiffLineType {
+        let origin = unsafe { (*self.raw).origin as u8 };
+        DiffLineType::from_raw(origin as raw::git_diff_line_t)
+    }
 }
 
 impl<'a> Binding for DiffLine<'a> {
@@ -1144,10 +1333,61 @@ impl DiffFindOptions {
     // TODO: expose git_diff_similarity_metric
 }
 
+impl Default for DiffFormatEmailOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiffFormatEmailOptions {
+    /// Creates a new set of email options, all set to their default values.
+    pub fn new() -> DiffFormatEmailOptions {
+        let mut opts = DiffFormatEmailOptions {
+            raw: unsafe { mem::zeroed() },
+            subject_prefix: None,
+            reroll_number: None,
+        };
+        assert_eq!(unsafe {
+            raw::git_diff_format_email_init_options(&mut opts.raw, 1)
+        }, 0);
+        opts
+    }
+
+    /// Don't insert the `[PATCH]` marker in the subject line.
+    pub fn exclude_subject_patch_marker(&mut self, exclude: bool)
+                                        -> &mut DiffFormatEmailOptions {
+        let flag = raw::GIT_DIFF_FORMAT_EMAIL_EXCLUDE_SUBJECT_PATCH_MARKER;
+        if exclude {
+            self.raw.flags |= flag;
+        } else {
+            self.raw.flags &= !flag;
+        }
+        self
+    }
+
+    /// Use `prefix` instead of `PATCH` in the subject marker, as with
+    /// `git format-patch --subject-prefix`.
+    pub fn subject_prefix(&mut self, prefix: &str)
+                          -> &mut DiffFormatEmailOptions {
+        self.subject_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Mark the patch as version `n` of the series in the subject marker,
+    /// such as `[PATCH v2 1/3]`, as with `git format-patch -v`.
+    pub fn reroll_number(&mut self, n: usize) -> &mut DiffFormatEmailOptions {
+        self.reroll_number = Some(n);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use DiffOptions;
+    use {Buf, Delta, DiffFindOptions, DiffFormat, DiffLineType, DiffOptions};
+    use {Diff, DiffFormatEmailOptions, Index, Patch};
+    use std::cell::{Cell, RefCell};
     use std::fs::File;
+    use std::rc::Rc;
     use std::path::Path;
     use std::borrow::Borrow;
     use std::io::Write;
@@ -1163,6 +1403,195 @@ mod tests {
         assert_eq!(stats.files_changed(), 0);
     }
 
+    #[test]
+    fn merge() {
+        let (td, repo) = ::test::repo_init();
+        t!(t!(File::create(&td.path().join("a"))).write_all(b"staged\n"));
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("a")));
+        t!(index.write());
+        t!(t!(File::create(&td.path().join("a"))).write_all(b"modified\n"));
+        t!(t!(File::create(&td.path().join("b"))).write_all(b"untracked\n"));
+
+        let head = t!(t!(repo.head()).peel_to_tree());
+        let mut staged = t!(repo.diff_tree_to_index(Some(&head), None, None));
+        assert_eq!(staged.deltas().len(), 1);
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true);
+        let unstaged = t!(repo.diff_index_to_workdir(None, Some(&mut opts)));
+        assert_eq!(unstaged.deltas().len(), 2);
+
+        t!(staged.merge(&unstaged));
+        assert_eq!(staged.deltas().len(), 2);
+        let paths = staged.deltas().map(|d| {
+            d.new_file().path().unwrap().to_path_buf()
+        }).collect::<Vec<_>>();
+        assert_eq!(paths, [Path::new("a"), Path::new("b")]);
+    }
+
+    #[test]
+    fn find_copies_from_unmodified() {
+        let (td, repo) = ::test::repo_init();
+        let contents = b"line 1\nline 2\nline 3\nline 4\n";
+        t!(t!(File::create(&td.path().join("a"))).write_all(contents));
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("a")));
+        let id = t!(index.write_tree());
+        let tree = t!(repo.find_tree(id));
+        t!(t!(File::create(&td.path().join("b"))).write_all(contents));
+        t!(index.add_path(Path::new("b")));
+
+        let find = |harder: bool| {
+            let mut opts = DiffOptions::new();
+            opts.include_unmodified(true);
+            let mut diff = t!(repo.diff_tree_to_index(Some(&tree),
+                                                      Some(&index),
+                                                      Some(&mut opts)));
+            let mut find = DiffFindOptions::new();
+            find.copies(true)
+                .copies_from_unmodified(harder)
+                .remove_unmodified(true);
+            t!(diff.find_similar(Some(&mut find)));
+            diff.deltas().map(|d| d.status()).collect::<Vec<_>>()
+        };
+        assert_eq!(find(false), [Delta::Added]);
+        assert_eq!(find(true), [Delta::Copied]);
+    }
+
+    #[test]
+    fn line_types() {
+        let (_td, repo) = ::test::repo_init();
+        let old = t!(repo.find_blob(t!(repo.blob(b"a\nb\n"))));
+        let new = t!(repo.find_blob(t!(repo.blob(b"a\nb"))));
+        let tree = |blob: &::Blob| {
+            let mut builder = t!(repo.treebuilder(None));
+            t!(builder.insert("file", blob.id(), 0o100644));
+            t!(repo.find_tree(t!(builder.write())))
+        };
+        let diff = t!(repo.diff_tree_to_tree(Some(&tree(&old)),
+                                             Some(&tree(&new)), None));
+
+        let mut types = Vec::new();
+        let mut linenos = Vec::new();
+        t!(diff.print(DiffFormat::Patch, |_, _, line| {
+            let kind = line.origin_value();
+            types.push(kind);
+            match kind {
+                DiffLineType::Context |
+                DiffLineType::Addition |
+                DiffLineType::Deletion => {
+                    linenos.push((line.old_lineno(), line.new_lineno()));
+                }
+                _ => {}
+            }
+            true
+        }));
+        assert_eq!(types, [
+            DiffLineType::FileHeader,
+            DiffLineType::HunkHeader,
+            DiffLineType::Context,
+            DiffLineType::Deletion,
+            DiffLineType::Addition,
+            DiffLineType::DelEOFNL,
+        ]);
+        for kind in types {
+            assert_eq!(DiffLineType::from_raw(kind.raw()), kind);
+        }
+        assert_eq!(DiffLineType::from_raw(b'?' as _),
+                   DiffLineType::Other('?'));
+        assert_eq!(DiffLineType::Other('?').raw(), b'?' as _);
+        assert_eq!(linenos, [
+            (Some(1), Some(1)),
+            (Some(2), None),
+            (None, Some(2)),
+        ]);
+    }
+
+    #[test]
+    fn numstat() {
+        let (td, repo) = ::test::repo_init();
+        let commit = |msg: &str| {
+            let mut index = t!(repo.index());
+            t!(index.add_all(["*"].iter(), ::IndexAddOption::DEFAULT, None));
+            t!(index.write());
+            let tree = t!(repo.find_tree(t!(index.write_tree())));
+            let sig = t!(repo.signature());
+            let head = t!(t!(repo.head()).peel_to_commit());
+            let id = t!(repo.commit(Some("HEAD"), &sig, &sig, msg, &tree,
+                                    &[&head]));
+            t!(repo.find_commit(id))
+        };
+        t!(t!(File::create(&td.path().join("a"))).write_all(b"1\n2\n3\n"));
+        t!(t!(File::create(&td.path().join("bin"))).write_all(b"\0\x01"));
+        let first = commit("first");
+        t!(t!(File::create(&td.path().join("a"))).write_all(b"1\nx\n3\n"));
+        t!(t!(File::create(&td.path().join("b"))).write_all(b"b1\nb2\n"));
+        t!(t!(File::create(&td.path().join("bin"))).write_all(b"\0\x02"));
+        let second = commit("second");
+
+        let diff = t!(repo.diff_tree_to_tree(Some(&t!(first.tree())),
+                                             Some(&t!(second.tree())),
+                                             None));
+        assert_eq!(t!(diff.numstat()), [
+            (Some((1, 1)), "a".to_string()),
+            (Some((2, 0)), "b".to_string()),
+            (None, "bin".to_string()),
+        ]);
+
+        let blob = t!(repo.blob(b"data\n"));
+        let tree = |mode: i32| {
+            let mut builder = t!(repo.treebuilder(None));
+            t!(builder.insert("mode", blob, mode));
+            t!(repo.find_tree(t!(builder.write())))
+        };
+        let diff = t!(repo.diff_tree_to_tree(Some(&tree(0o100644)),
+                                             Some(&tree(0o100755)),
+                                             None));
+        assert_eq!(t!(diff.numstat()), [(Some((0, 0)), "mode".to_string())]);
+    }
+
+    #[test]
+    fn progress() {
+        let (_td, repo) = ::test::repo_init();
+        let tree = |files: &[(&str, &[u8])]| {
+            let mut builder = t!(repo.treebuilder(None));
+            for &(name, data) in files {
+                let blob = t!(repo.blob(data));
+                t!(builder.insert(name, blob, 0o100644));
+            }
+            t!(repo.find_tree(t!(builder.write())))
+        };
+        let old = tree(&[("a", b"a"), ("b", b"b"), ("c", b"c")]);
+        let new = tree(&[("a", b"a"), ("b", b"b2"), ("d", b"d")]);
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen2 = seen.clone();
+        let mut opts = DiffOptions::new();
+        opts.progress(move |old, new| {
+            let path = new.or(old).unwrap().to_path_buf();
+            seen2.borrow_mut().push(path);
+            true
+        });
+        let diff = t!(repo.diff_tree_to_tree(Some(&old), Some(&new),
+                                             Some(&mut opts)));
+        assert_eq!(diff.deltas().len(), 3);
+        let seen = RefCell::borrow(&seen);
+        for name in ["b", "c", "d"].iter() {
+            assert!(seen.iter().any(|p| p == Path::new(name)));
+        }
+
+        let count = Rc::new(Cell::new(0));
+        let count2 = count.clone();
+        let mut opts = DiffOptions::new();
+        opts.progress(move |_, _| {
+            count2.set(count2.get() + 1);
+            false
+        });
+        assert!(repo.diff_tree_to_tree(Some(&old), Some(&new),
+                                       Some(&mut opts)).is_err());
+        assert_eq!(count.get(), 1);
+    }
+
     #[test]
     fn foreach_smoke() {
         let (_td, repo) = ::test::repo_init();
@@ -1255,4 +1684,267 @@ mod tests {
         assert_eq!(new_lines, 1);
         assert_eq!(line_content, Some("bar\n".to_string()));
     }
+
+    #[test]
+    fn foreach_binary_modified() {
+        let mut v1 = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        let mut v2 = v1.clone();
+        v1.extend((0..64).map(|i| i as u8));
+        v2.extend((0..64).map(|i| (i * 3) as u8));
+        let path = Path::new("image.png");
+        let (td, repo) = ::test::repo_init();
+        t!(t!(File::create(&td.path().join(path))).write_all(&v1));
+        let mut index = t!(repo.index());
+        t!(index.add_path(path));
+        t!(t!(File::create(&td.path().join(path))).write_all(&v2));
+
+        let mut opts = DiffOptions::new();
+        opts.show_binary(true);
+        let diff = t!(repo.diff_index_to_workdir(Some(&index),
+                                                 Some(&mut opts)));
+        let mut seen = Vec::new();
+        t!(diff.foreach(
+            &mut |_file, _progress| { true },
+            Some(&mut |delta, binary| {
+                assert_eq!(delta.new_file().path(), Some(path));
+                assert!(binary.contains_data());
+                seen.push((binary.old_file().data().len(),
+                           binary.new_file().data().len()));
+                true
+            }),
+            None,
+            None));
+        assert_eq!(seen.len(), 1);
+        assert!(seen[0].0 > 0);
+        assert!(seen[0].1 > 0);
+    }
+
+    #[test]
+    fn show_binary_patch() {
+        let (_td, repo) = ::test::repo_init();
+        let old = t!(repo.find_blob(t!(repo.blob(b"\0\x01\x02binary\n"))));
+        let new = t!(repo.find_blob(t!(repo.blob(b"\0\x01\x03binary\n"))));
+        let path = Path::new("data.bin");
+        let to_text = |opts: &mut DiffOptions| {
+            let mut patch = t!(Patch::from_blobs(&old, Some(path), &new,
+                                                 Some(path), Some(opts)));
+            let buf = t!(patch.to_buf());
+            String::from_utf8_lossy(&buf).into_owned()
+        };
+
+        let text = to_text(&mut DiffOptions::new());
+        assert!(text.contains("Binary files"));
+        assert!(!text.contains("GIT binary patch"));
+        let text = to_text(DiffOptions::new().show_binary(true));
+        assert!(text.contains("GIT binary patch"));
+
+        let mut opts = DiffOptions::new();
+        opts.force_binary(true);
+        let mut patch = t!(Patch::from_buffers(b"a\n", Some(path), b"b\n",
+                                               Some(path), Some(&mut opts)));
+        assert!(String::from_utf8_lossy(&t!(patch.to_buf()))
+            .contains("Binary files"));
+    }
+
+    #[test]
+    fn format_email() {
+        let (td, repo) = ::test::repo_init();
+        let parent = t!(repo.head()).target().unwrap();
+        let parent = t!(repo.find_commit(parent));
+        t!(t!(File::create(&td.path().join("foo"))).write_all(b"bar\n"));
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("foo")));
+        let tree = t!(repo.find_tree(t!(index.write_tree())));
+        let sig = t!(repo.signature());
+        let id = t!(repo.commit(None, &sig, &sig, "add foo\n\nbody text\n",
+                                &tree, &[&parent]));
+        let commit = t!(repo.find_commit(id));
+        let diff = t!(repo.diff_tree_to_tree(Some(&t!(parent.tree())),
+                                             Some(&tree), None));
+
+        let buf = t!(diff.format_email(1, 3, &commit, None));
+        let email = buf.as_str().unwrap();
+        assert!(email.starts_with(&format!("From {} ", id)));
+        assert!(email.contains("Subject: [PATCH 1/3] add foo\n"));
+        assert!(email.contains("body text\n"));
+        assert!(email.contains("+bar\n"));
+
+        let mut opts = DiffFormatEmailOptions::new();
+        opts.exclude_subject_patch_marker(true);
+        let buf = t!(diff.format_email(1, 1, &commit, Some(&opts)));
+        assert!(buf.as_str().unwrap().contains("Subject: add foo\n"));
+        assert!(diff.format_email(4, 3, &commit, None).is_err());
+
+        let mut opts = DiffFormatEmailOptions::new();
+        opts.reroll_number(2);
+        let buf = t!(diff.format_email(1, 3, &commit, Some(&opts)));
+        assert!(t!(buf.as_str()).contains("Subject: [PATCH v2 1/3] add foo\n"));
+        assert!(diff.format_email(4, 3, &commit, Some(&opts)).is_err());
+        assert!(diff.format_email(0, 3, &commit, Some(&opts)).is_err());
+        opts.subject_prefix("RFC");
+        let buf = t!(diff.format_email(1, 1, &commit, Some(&opts)));
+        assert!(t!(buf.as_str()).contains("Subject: [RFC v2] add foo\n"));
+        opts.exclude_subject_patch_marker(true);
+        let buf = t!(diff.format_email(1, 1, &commit, Some(&opts)));
+        assert!(t!(buf.as_str()).contains("Subject: add foo\n"));
+    }
+
+    #[test]
+    fn from_buffer() {
+        let patch = b"\
+diff --git a/foo b/foo
+index 7898192..6178079 100644
+--- a/foo
++++ b/foo
+@@ -1,2 +1,2 @@
+ a
+-b
++c
+@@ -10 +10,2 @@
+ x
++y
+diff --git a/bar b/bar
+new file mode 100644
+index 0000000..257cc56
+--- /dev/null
++++ b/bar
+@@ -0,0 +1 @@
++foo
+";
+        let diff = t!(Diff::from_buffer(patch));
+        assert_eq!(diff.deltas().len(), 2);
+        let delta = diff.get_delta(0).unwrap();
+        assert_eq!(delta.status(), Delta::Modified);
+        assert_eq!(delta.new_file().path(), Some(Path::new("foo")));
+        assert_eq!(diff.get_delta(1).unwrap().status(), Delta::Added);
+
+        let patch0 = t!(Patch::from_diff(&diff, 0)).unwrap();
+        assert_eq!(patch0.num_hunks(), 2);
+        assert_eq!(t!(patch0.line_stats()), (2, 2, 1));
+        let patch1 = t!(Patch::from_diff(&diff, 1)).unwrap();
+        assert_eq!(patch1.num_hunks(), 1);
+
+        let stats = t!(diff.stats());
+        assert_eq!(stats.files_changed(), 2);
+        assert_eq!(stats.insertions(), 3);
+        assert_eq!(stats.deletions(), 1);
+    }
+
+    #[test]
+    fn from_buffer_malformed() {
+        let patch = b"\
+diff --git a/foo b/foo
+index 7898192..6178079 100644
+--- a/foo
++++ b/foo
+@@ -1,5 +1,5 @@
+ a
+-b
++c
+";
+        assert!(Diff::from_buffer(patch).is_err());
+    }
+
+    #[test]
+    fn tree_to_workdir_with_index() {
+        let (td, repo) = ::test::repo_init();
+        let write = |name: &str, data: &[u8]| {
+            t!(t!(File::create(td.path().join(name))).write_all(data));
+        };
+        write("staged", b"a\n");
+        write("unstaged", b"b\n");
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("staged")));
+        t!(index.add_path(Path::new("unstaged")));
+        let tree = t!(repo.find_tree(t!(index.write_tree())));
+        let sig = t!(repo.signature());
+        let parent = t!(t!(repo.head()).peel_to_commit());
+        t!(repo.commit(Some("HEAD"), &sig, &sig, "files", &tree, &[&parent]));
+
+        write("staged", b"a\nchanged\n");
+        t!(index.add_path(Path::new("staged")));
+        t!(index.write());
+        write("unstaged", b"b\nchanged\n");
+
+        let diff = t!(repo.diff_tree_to_index(Some(&tree), None, None));
+        assert_eq!(diff.deltas().len(), 1);
+        let diff = t!(repo.diff_index_to_workdir(None, None));
+        assert_eq!(diff.deltas().len(), 1);
+
+        let diff = t!(repo.diff_tree_to_workdir_with_index(Some(&tree), None));
+        let paths = diff.deltas().map(|d| {
+            assert_eq!(d.status(), Delta::Modified);
+            d.new_file().path().unwrap().to_path_buf()
+        }).collect::<Vec<_>>();
+        assert_eq!(paths, [Path::new("staged"), Path::new("unstaged")]);
+    }
+
+    #[test]
+    fn stats_between_commits() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let commit = |data: &[u8], parent: &::Commit| {
+            let tree = ::test::single_file_tree(&repo, data);
+            let id = t!(repo.commit(None, &sig, &sig, "edit", &tree,
+                                    &[parent]));
+            t!(repo.find_commit(id))
+        };
+        let head = t!(t!(repo.head()).peel_to_commit());
+        let old = commit(b"a\nb\nc\n", &head);
+        let new = commit(b"a\nx\ny\nz\nc\n", &old);
+
+        let diff = t!(repo.diff_tree_to_tree(Some(&t!(old.tree())),
+                                             Some(&t!(new.tree())), None));
+        let stats = t!(diff.stats());
+        assert_eq!(stats.insertions(), 3);
+        assert_eq!(stats.deletions(), 1);
+        assert_eq!(stats.files_changed(), 1);
+    }
+
+    #[test]
+    fn index_to_index() {
+        let (_td, repo) = ::test::repo_init();
+        let blob = t!(repo.blob(b"data"));
+        let index = |names: &[&str]| {
+            let mut builder = t!(repo.treebuilder(None));
+            for name in names {
+                t!(builder.insert(name, blob, 0o100644));
+            }
+            let tree = t!(repo.find_tree(t!(builder.write())));
+            let mut index = t!(Index::new());
+            t!(index.read_tree(&tree));
+            index
+        };
+        let old = index(&["a", "b"]);
+        let new = index(&["a", "b", "c"]);
+
+        let diff = t!(repo.diff_index_to_index(&old, &new, None));
+        assert_eq!(diff.deltas().len(), 1);
+        let delta = diff.get_delta(0).unwrap();
+        assert_eq!(delta.status(), Delta::Added);
+        assert_eq!(delta.new_file().path(), Some(Path::new("c")));
+
+        let diff = t!(repo.diff_index_to_index(&old, &old, None));
+        assert_eq!(diff.deltas().len(), 0);
+    }
+
+    #[test]
+    fn to_buf_reuse() {
+        let (td, repo) = ::test::repo_init();
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true).show_untracked_content(true);
+        let mut buf = Buf::new();
+        for i in 0..10 {
+            let line = format!("line {}\n", i);
+            t!(t!(File::create(&td.path().join("foo"))).write_all(line.as_bytes()));
+            let diff = t!(repo.diff_tree_to_workdir(None, Some(&mut opts)));
+            buf.clear();
+            assert_eq!(buf.len(), 0);
+            t!(diff.to_buf_into(DiffFormat::Patch, &mut buf));
+            let text = buf.as_str().unwrap();
+            assert!(text.contains(&format!("+{}", line)));
+            assert_eq!(text.matches("diff --git").count(), 1);
+            assert_eq!(&*t!(diff.to_buf(DiffFormat::Patch)), &*buf);
+        }
+    }
 }
@@ -2,7 +2,7 @@ use std::env::JoinPathsError;
 use std::ffi::{CStr, NulError};
 use std::error;
 use std::fmt;
-use std::str;
+use std::str::{self, Utf8Error};
 use libc::c_int;
 
 use {raw, ErrorClass, ErrorCode};
@@ -262,6 +262,13 @@ impl From<NulError> for Error {
     }
 }
 
+impl From<Utf8Error> for Error {
+    fn from(e: Utf8Error) -> Error {
+        Error::from_str(&format!("data was not valid utf-8, invalid byte \
+                                  sequence at offset {}", e.valid_up_to()))
+    }
+}
+
 impl From<JoinPathsError> for Error {
     fn from(e: JoinPathsError) -> Error {
         Error::from_str(error::Error::description(&e))
@@ -0,0 +1,98 @@
+use std::ffi::CString;
+use std::marker;
+
+use libc::c_char;
+
+use {raw, Blob, Buf, Error, Repository};
+use util::Binding;
+
+/// A list of filters to be applied to a file or blob.
+///
+/// A filter list is loaded for a particular path with
+/// `Repository::filter_list_load` and can then be used to run the same
+/// smudge or clean pipeline that libgit2 uses during checkout and add.
+pub struct FilterList<'repo> {
+    raw: *mut raw::git_filter_list,
+    _marker: marker::PhantomData<&'repo Repository>,
+}
+
+impl<'repo> FilterList<'repo> {
+    /// Query whether a filter with the given name, such as `crlf` or
+    /// `ident`, will run as part of this list.
+    pub fn contains(&self, name: &str) -> Result<bool, Error> {
+        let name = try!(CString::new(name));
+        unsafe {
+            Ok(raw::git_filter_list_contains(self.raw, name.as_ptr()) == 1)
+        }
+    }
+
+    /// Apply the filters in this list to a data buffer, returning the
+    /// filtered contents.
+    pub fn apply_to_buffer(&self, input: &[u8]) -> Result<Buf, Error> {
+        let buf = Buf::new();
+        // An `asize` of 0 tells libgit2 that it does not own this memory.
+        let mut input = raw::git_buf {
+            ptr: input.as_ptr() as *mut c_char,
+            asize: 0,
+            size: input.len(),
+        };
+        unsafe {
+            try_call!(raw::git_filter_list_apply_to_data(buf.raw(), self.raw,
+                                                         &mut input));
+        }
+        Ok(buf)
+    }
+
+    /// Apply the filters in this list to the contents of a blob, returning
+    /// the filtered contents.
+    pub fn apply_to_blob(&self, blob: &Blob) -> Result<Buf, Error> {
+        let buf = Buf::new();
+        unsafe {
+            try_call!(raw::git_filter_list_apply_to_blob(buf.raw(), self.raw,
+                                                         blob.raw()));
+        }
+        Ok(buf)
+    }
+}
+
+impl<'repo> Binding for FilterList<'repo> {
+    type Raw = *mut raw::git_filter_list;
+
+    unsafe fn from_raw(raw: *mut raw::git_filter_list) -> FilterList<'repo> {
+        FilterList { raw: raw, _marker: marker::PhantomData }
+    }
+    fn raw(&self) -> *mut raw::git_filter_list { self.raw }
+}
+
+impl<'repo> Drop for FilterList<'repo> {
+    fn drop(&mut self) {
+        unsafe { raw::git_filter_list_free(self.raw) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use {FilterFlags, FilterMode};
+
+    #[test]
+    fn autocrlf() {
+        let (_td, repo) = ::test::repo_init();
+        repo.config().unwrap().set_bool("core.autocrlf", true).unwrap();
+
+        let filters = repo.filter_list_load(None, Path::new("foo.txt"),
+                                            FilterMode::ToWorktree,
+                                            FilterFlags::DEFAULT).unwrap();
+        let filters = filters.unwrap();
+        assert!(filters.contains("crlf").unwrap());
+        let buf = filters.apply_to_buffer(b"a\nb\n").unwrap();
+        assert_eq!(&*buf, b"a\r\nb\r\n");
+
+        let filters = repo.filter_list_load(None, Path::new("foo.txt"),
+                                            FilterMode::ToOdb,
+                                            FilterFlags::DEFAULT).unwrap();
+        let buf = filters.unwrap().apply_to_buffer(b"a\r\nb\r\n").unwrap();
+        assert_eq!(&*buf, b"a\nb\n");
+    }
+}
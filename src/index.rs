@@ -7,6 +7,7 @@ use std::slice;
 use libc::{c_int, c_uint, size_t, c_void, c_char};
 
 use {raw, panic, Repository, Error, Tree, Oid, IndexAddOption, IndexTime};
+use IndexCapabilities;
 use IntoCString;
 use util::{self, Binding};
 
@@ -89,40 +90,34 @@ impl Index {
     /// will be added.
     pub fn add(&mut self, entry: &IndexEntry) -> Result<(), Error> {
         let path = try!(CString::new(&entry.path[..]));
-
-        // libgit2 encodes the length of the path in the lower bits of the
-        // `flags` entry, so mask those out and recalculate here to ensure we
-        // don't corrupt anything.
-        let mut flags = entry.flags & !raw::GIT_IDXENTRY_NAMEMASK;
-
-        if entry.path.len() < raw::GIT_IDXENTRY_NAMEMASK as usize {
-            flags |= entry.path.len() as u16;
-        } else {
-            flags |= raw::GIT_IDXENTRY_NAMEMASK;
+        unsafe {
+            let raw = raw_entry(entry, &path);
+            try_call!(raw::git_index_add(self.raw, &raw));
+            Ok(())
         }
+    }
 
+    /// Add or update an index entry from a buffer in memory
+    ///
+    /// This method will create a blob in the repository that owns the index
+    /// and then add the index entry to the index. The path of the entry
+    /// represents the position of the blob relative to the repository's root
+    /// folder.
+    ///
+    /// If a previous index entry exists that has the same path as the given
+    /// 'entry', it will be replaced. Otherwise, the 'entry' will be added.
+    /// The id and file size of the given entry are ignored.
+    ///
+    /// This forces the file to be added to the index, not looking at gitignore
+    /// rules.
+    pub fn add_frombuffer(&mut self, entry: &IndexEntry, data: &[u8])
+                          -> Result<(), Error> {
+        let path = try!(CString::new(&entry.path[..]));
         unsafe {
-            let raw = raw::git_index_entry {
-                dev: entry.dev,
-                ino: entry.ino,
-                mode: entry.mode,
-                uid: entry.uid,
-                gid: entry.gid,
-                file_size: entry.file_size,
-                id: *entry.id.raw(),
-                flags: flags,
-                flags_extended: entry.flags_extended,
-                path: path.as_ptr(),
-                mtime: raw::git_index_time {
-                    seconds: entry.mtime.seconds(),
-                    nanoseconds: entry.mtime.nanoseconds(),
-                },
-                ctime: raw::git_index_time {
-                    seconds: entry.ctime.seconds(),
-                    nanoseconds: entry.ctime.nanoseconds(),
-                },
-            };
-            try_call!(raw::git_index_add(self.raw, &raw));
+            let raw = raw_entry(entry, &path);
+            let ptr = data.as_ptr() as *const c_void;
+            let len = data.len() as size_t;
+            try_call!(raw::git_index_add_frombuffer(self.raw, &raw, ptr, len));
             Ok(())
         }
     }
@@ -243,7 +238,10 @@ impl Index {
         IndexEntries { range: 0..self.len(), index: self }
     }
 
-    /// Get one of the entries in the index by its path.
+    /// Get one of the entries in the index by its path and stage.
+    ///
+    /// Pass a `stage` of 0 for the normal entry, or 1, 2 and 3 for the
+    /// ancestor, "ours" and "theirs" sides of a conflict.
     pub fn get_path(&self, path: &Path, stage: i32) -> Option<IndexEntry> {
         let path = path.into_c_string().unwrap();
         unsafe {
@@ -253,6 +251,68 @@ impl Index {
         }
     }
 
+    /// Find the position of the stage 0 entry for `path` in the index.
+    ///
+    /// Returns an error with the `NotFound` code if there is no such entry.
+    pub fn find(&self, path: &Path) -> Result<usize, Error> {
+        let path = try!(path.into_c_string());
+        let mut pos = 0;
+        unsafe {
+            try_call!(raw::git_index_find(&mut pos, self.raw, path));
+        }
+        Ok(pos as usize)
+    }
+
+    /// Find the position of the first entry in the index whose path starts
+    /// with `prefix`.
+    ///
+    /// Returns an error with the `NotFound` code if there is no such entry.
+    pub fn find_prefix(&self, prefix: &Path) -> Result<usize, Error> {
+        let prefix = try!(prefix.into_c_string());
+        let mut pos = 0;
+        unsafe {
+            try_call!(raw::git_index_find_prefix(&mut pos, self.raw, prefix));
+        }
+        Ok(pos as usize)
+    }
+
+    /// Get the on-disk format version of this index.
+    ///
+    /// Valid return values are 2, 3 or 4. If 3 is returned, an index with
+    /// version 2 may be written instead if no extended entries are present.
+    pub fn version(&self) -> u32 {
+        unsafe { raw::git_index_version(self.raw) as u32 }
+    }
+
+    /// Set the on-disk format version of this index.
+    ///
+    /// Valid values are 2, 3 or 4. The new version takes effect the next
+    /// time the index is written.
+    pub fn set_version(&mut self, version: u32) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_index_set_version(self.raw, version as c_uint));
+        }
+        Ok(())
+    }
+
+    /// Get the capabilities of this index.
+    pub fn caps(&self) -> IndexCapabilities {
+        let caps = unsafe { raw::git_index_caps(&*self.raw) };
+        IndexCapabilities::from_bits_truncate(caps as u32)
+    }
+
+    /// Set the capabilities of this index.
+    ///
+    /// Capabilities are usually derived from the configuration of the owning
+    /// repository (`core.ignorecase`, `core.filemode` and `core.symlinks`);
+    /// this overrides them.
+    pub fn set_caps(&mut self, caps: IndexCapabilities) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_index_set_caps(self.raw, caps.bits() as c_int));
+        }
+        Ok(())
+    }
+
     /// Does this index have conflicts?
     ///
     /// Returns `true` if the index contains conflicts, `false` if it does not.
@@ -460,6 +520,22 @@ impl Drop for Index {
     }
 }
 
+impl IndexEntry {
+    /// Returns the stage number of this entry.
+    ///
+    /// An entry which is not part of a conflict has stage 0, otherwise the
+    /// stage is 1 for the common ancestor, 2 for "ours" and 3 for "theirs".
+    pub fn stage(&self) -> i32 {
+        ((self.flags & raw::GIT_IDXENTRY_STAGEMASK) >>
+            raw::GIT_IDXENTRY_STAGESHIFT) as i32
+    }
+
+    /// Returns whether this entry is one side of a conflict.
+    pub fn is_conflict(&self) -> bool {
+        self.stage() > 0
+    }
+}
+
 impl<'index> Iterator for IndexEntries<'index> {
     type Item = IndexEntry;
     fn next(&mut self) -> Option<IndexEntry> {
@@ -467,6 +543,46 @@ impl<'index> Iterator for IndexEntries<'index> {
     }
 }
 
+/// Creates the raw form of `entry` for passing to libgit2.
+///
+/// The `path` must be the entry's path and must outlive the returned value,
+/// which points into it.
+pub fn raw_entry(entry: &IndexEntry, path: &CString) -> raw::git_index_entry {
+    // libgit2 encodes the length of the path in the lower bits of the
+    // `flags` entry, so mask those out and recalculate here to ensure we
+    // don't corrupt anything.
+    let mut flags = entry.flags & !raw::GIT_IDXENTRY_NAMEMASK;
+
+    if entry.path.len() < raw::GIT_IDXENTRY_NAMEMASK as usize {
+        flags |= entry.path.len() as u16;
+    } else {
+        flags |= raw::GIT_IDXENTRY_NAMEMASK;
+    }
+
+    unsafe {
+        raw::git_index_entry {
+            dev: entry.dev,
+            ino: entry.ino,
+            mode: entry.mode,
+            uid: entry.uid,
+            gid: entry.gid,
+            file_size: entry.file_size,
+            id: *entry.id.raw(),
+            flags: flags,
+            flags_extended: entry.flags_extended,
+            path: path.as_ptr(),
+            mtime: raw::git_index_time {
+                seconds: entry.mtime.seconds(),
+                nanoseconds: entry.mtime.nanoseconds(),
+            },
+            ctime: raw::git_index_time {
+                seconds: entry.ctime.seconds(),
+                nanoseconds: entry.ctime.nanoseconds(),
+            },
+        }
+    }
+}
+
 impl Binding for IndexEntry {
     type Raw = raw::git_index_entry;
 
@@ -511,10 +627,12 @@ impl Binding for IndexEntry {
 #[cfg(test)]
 mod tests {
     use std::fs::{self, File};
+    use std::io::prelude::*;
     use std::path::Path;
     use tempdir::TempDir;
 
-    use {Index, IndexEntry, Repository, ResetType, Oid, IndexTime};
+    use {Index, IndexCapabilities, IndexEntry, Repository, ResetType, Oid};
+    use {ErrorCode, IndexTime};
 
     #[test]
     fn smoke() {
@@ -527,6 +645,24 @@ mod tests {
         assert!(index.read(true).is_err());
     }
 
+    #[test]
+    fn remove_dir() {
+        let (td, repo) = ::test::repo_init();
+        let mut index = repo.index().unwrap();
+        for path in &["dir/a", "dir/sub/b", "dir2/c", "d"] {
+            let path = Path::new(path);
+            let full = td.path().join(path);
+            fs::create_dir_all(full.parent().unwrap()).unwrap();
+            File::create(&full).unwrap().write_all(b"x").unwrap();
+            index.add_path(path).unwrap();
+        }
+        assert_eq!(index.len(), 4);
+
+        index.remove_dir(Path::new("dir"), 0).unwrap();
+        let paths = index.iter().map(|e| e.path).collect::<Vec<_>>();
+        assert_eq!(paths, vec![b"d".to_vec(), b"dir2/c".to_vec()]);
+    }
+
     #[test]
     fn smoke_from_repo() {
         let (_td, repo) = ::test::repo_init();
@@ -572,6 +708,38 @@ mod tests {
         assert!(called);
     }
 
+    #[test]
+    fn update_all() {
+        let (_td, repo) = ::test::repo_init();
+        let mut index = repo.index().unwrap();
+
+        let root = repo.path().parent().unwrap();
+        File::create(&root.join("modified")).unwrap()
+            .write_all(b"old").unwrap();
+        File::create(&root.join("deleted")).unwrap();
+        index.add_path(Path::new("modified")).unwrap();
+        index.add_path(Path::new("deleted")).unwrap();
+
+        File::create(&root.join("modified")).unwrap()
+            .write_all(b"new contents").unwrap();
+        fs::remove_file(&root.join("deleted")).unwrap();
+        File::create(&root.join("untracked")).unwrap();
+
+        let mut called = 0;
+        index.update_all(["*"].iter(), Some(&mut |_: &Path, _: &[u8]| {
+            called += 1;
+            0
+        })).unwrap();
+        assert_eq!(called, 2);
+
+        let e = index.get_path(Path::new("modified"), 0).unwrap();
+        assert_eq!(e.id, Oid::hash_object(::ObjectType::Blob,
+                                         b"new contents").unwrap());
+        assert!(index.get_path(Path::new("deleted"), 0).is_none());
+        assert!(index.get_path(Path::new("untracked"), 0).is_none());
+        assert_eq!(index.len(), 1);
+    }
+
     #[test]
     fn smoke_add() {
         let (_td, repo) = ::test::repo_init();
@@ -615,6 +783,107 @@ mod tests {
         assert_eq!(e.path.len(), 6);
     }
 
+    #[test]
+    fn add_frombuffer_then_read() {
+        let (_td, repo) = ::test::repo_init();
+        let mut index = repo.index().unwrap();
+
+        let mut e = entry();
+        e.path = b"generated.txt".to_vec();
+        let content = b"generated content\n";
+        index.add_frombuffer(&e, content).unwrap();
+        let e = index.get_path(Path::new("generated.txt"), 0).unwrap();
+        assert_eq!(e.file_size, content.len() as u32);
+
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let id = tree.get_name("generated.txt").unwrap().id();
+        assert_eq!(id, e.id);
+        assert_eq!(repo.find_blob(id).unwrap().content(), content);
+    }
+
+    #[test]
+    fn find() {
+        let mut index = Index::new().unwrap();
+        for path in &["a", "dir/b", "dir/c", "e"] {
+            let mut e = entry();
+            e.path = path.as_bytes().to_vec();
+            index.add(&e).unwrap();
+        }
+
+        assert_eq!(index.find(Path::new("a")).unwrap(), 0);
+        assert_eq!(index.find(Path::new("dir/c")).unwrap(), 2);
+        assert_eq!(index.find(Path::new("e")).unwrap(), 3);
+        let pos = index.find(Path::new("dir/b")).unwrap();
+        assert_eq!(index.get(pos).unwrap().path, b"dir/b");
+        let err = index.find(Path::new("missing")).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+
+        assert_eq!(index.find_prefix(Path::new("dir/")).unwrap(), 1);
+        assert_eq!(index.find_prefix(Path::new("e")).unwrap(), 3);
+        let err = index.find_prefix(Path::new("x")).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+    }
+
+    #[test]
+    fn conflict_stages() {
+        let (_td, repo) = ::test::repo_init();
+        let tree = |data: &[u8]| ::test::single_file_tree(&repo, data);
+        let ancestor = tree(b"ancestor");
+        let ours = tree(b"ours");
+        let theirs = tree(b"theirs");
+
+        let index = repo.merge_trees(&ancestor, &ours, &theirs, None).unwrap();
+        assert!(index.has_conflicts());
+        let entries = index.iter().collect::<Vec<_>>();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|e| e.is_conflict()));
+        let stages = entries.iter().map(|e| e.stage()).collect::<Vec<_>>();
+        assert_eq!(stages, [1, 2, 3]);
+        assert!(!entry().is_conflict());
+    }
+
+    #[test]
+    fn get_path_conflict_stage() {
+        let (_td, repo) = ::test::repo_init();
+        let blob = |data: &[u8]| repo.blob(data).unwrap();
+        let tree = |id| {
+            let mut builder = repo.treebuilder(None).unwrap();
+            builder.insert("file", id, 0o100644).unwrap();
+            repo.find_tree(builder.write().unwrap()).unwrap()
+        };
+        let (ours, theirs) = (blob(b"ours"), blob(b"theirs"));
+        let index = repo.merge_trees(&tree(blob(b"ancestor")), &tree(ours),
+                                     &tree(theirs), None).unwrap();
+
+        let path = Path::new("file");
+        assert!(index.get_path(path, 0).is_none());
+        let e = index.get_path(path, 2).unwrap();
+        assert_eq!(e.stage(), 2);
+        assert_eq!(e.id, ours);
+        assert_eq!(index.get_path(path, 3).unwrap().id, theirs);
+    }
+
+    #[test]
+    fn version_and_caps() {
+        let (_td, repo) = ::test::repo_init();
+        let mut index = repo.index().unwrap();
+        assert!(index.version() == 2 || index.version() == 3);
+        index.set_version(4).unwrap();
+        assert_eq!(index.version(), 4);
+        assert!(index.set_version(1).is_err());
+        index.write().unwrap();
+
+        let index = Index::open(&repo.path().join("index")).unwrap();
+        assert_eq!(index.version(), 4);
+
+        let mut index = Index::new().unwrap();
+        index.set_caps(IndexCapabilities::IGNORE_CASE).unwrap();
+        assert!(index.caps().is_ignore_case());
+        assert!(!index.caps().is_no_symlinks());
+        index.set_caps(IndexCapabilities::empty()).unwrap();
+        assert!(!index.caps().is_ignore_case());
+    }
+
     fn entry() -> IndexEntry {
         IndexEntry {
             ctime: IndexTime::new(0, 0),
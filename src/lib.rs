@@ -84,6 +84,7 @@ pub use blame::{Blame, BlameHunk, BlameIter, BlameOptions};
 pub use blob::{Blob, BlobWriter};
 pub use branch::{Branch, Branches};
 pub use buf::Buf;
+pub use cherrypick::CherrypickOptions;
 pub use commit::{Commit, Parents};
 pub use config::{Config, ConfigEntry, ConfigEntries};
 pub use cred::{Cred, CredentialHelper};
@@ -91,9 +92,12 @@ pub use describe::{Describe, DescribeFormatOptions, DescribeOptions};
 pub use diff::{Diff, DiffDelta, DiffFile, DiffOptions, Deltas};
 pub use diff::{DiffBinary, DiffBinaryFile, DiffBinaryKind};
 pub use diff::{DiffLine, DiffHunk, DiffStats, DiffFindOptions};
+pub use diff::DiffFormatEmailOptions;
 pub use error::Error;
+pub use filter::FilterList;
 pub use index::{Index, IndexEntry, IndexEntries, IndexMatchedPath};
 pub use merge::{AnnotatedCommit, MergeOptions};
+pub use merge::{MergeFileOptions, MergeFileResult};
 pub use message::{message_prettify, DEFAULT_COMMENT_CHAR};
 pub use note::{Note, Notes};
 pub use object::Object;
@@ -103,13 +107,16 @@ pub use pathspec::{Pathspec, PathspecMatchList, PathspecFailedEntries};
 pub use pathspec::{PathspecDiffEntries, PathspecEntries};
 pub use patch::Patch;
 pub use proxy_options::ProxyOptions;
-pub use reference::{Reference, References, ReferenceNames};
+pub use rebase::{Rebase, RebaseOptions, RebaseOperation, RebaseOperationType};
+pub use refdb::Refdb;
+pub use reference::{Reference, References, ReferenceNames, PeeledReferences};
 pub use reflog::{Reflog, ReflogEntry, ReflogIter};
 pub use refspec::Refspec;
 pub use remote::{Remote, RemoteConnection, Refspecs, RemoteHead, FetchOptions, PushOptions};
 pub use remote_callbacks::{RemoteCallbacks, Credentials, TransferProgress};
 pub use remote_callbacks::{TransportMessage, Progress, UpdateTips};
-pub use repo::{Repository, RepositoryInitOptions};
+pub use repo::{CommitSpec, Repository, RepositoryInitOptions};
+pub use revert::RevertOptions;
 pub use revspec::Revspec;
 pub use revwalk::Revwalk;
 pub use signature::Signature;
@@ -117,11 +124,13 @@ pub use status::{StatusOptions, Statuses, StatusIter, StatusEntry, StatusShow};
 pub use stash::{StashApplyOptions, StashCb, StashApplyProgressCb};
 pub use submodule::{Submodule, SubmoduleUpdateOptions};
 pub use tag::Tag;
-pub use time::{Time, IndexTime};
+pub use time::{Time, IndexTime, TimeFormat};
 pub use tree::{Tree, TreeEntry, TreeIter};
 pub use treebuilder::TreeBuilder;
-pub use odb::{Odb, OdbObject, OdbReader, OdbWriter};
+pub use odb::{Odb, OdbExpandId, OdbObject, OdbReader, OdbWriter};
+pub use odb::OdbWritepack;
 pub use util::IntoCString;
+pub use worktree::Worktree;
 
 // Create a convinience method on bitflag struct which checks the given flag
 macro_rules! is_bit_set {
@@ -305,6 +314,37 @@ pub enum ObjectType {
     Tag,
 }
 
+/// An enumeration of the file modes an entry in a tree may have.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum FileMode {
+    /// Unreadable
+    Unreadable,
+    /// A subdirectory (0o040000)
+    Tree,
+    /// A regular, non-executable file (0o100644)
+    Blob,
+    /// An executable file (0o100755)
+    BlobExecutable,
+    /// A symbolic link (0o120000)
+    Link,
+    /// A gitlink to a commit, as used by submodules (0o160000)
+    Commit,
+}
+
+impl From<FileMode> for i32 {
+    fn from(mode: FileMode) -> i32 {
+        let raw = match mode {
+            FileMode::Unreadable => raw::GIT_FILEMODE_UNREADABLE,
+            FileMode::Tree => raw::GIT_FILEMODE_TREE,
+            FileMode::Blob => raw::GIT_FILEMODE_BLOB,
+            FileMode::BlobExecutable => raw::GIT_FILEMODE_BLOB_EXECUTABLE,
+            FileMode::Link => raw::GIT_FILEMODE_LINK,
+            FileMode::Commit => raw::GIT_FILEMODE_COMMIT,
+        };
+        raw as i32
+    }
+}
+
 /// An enumeration of all possile kinds of references.
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum ReferenceType {
@@ -532,6 +572,25 @@ impl Default for IndexAddOption {
     }
 }
 
+bitflags! {
+    /// Capabilities of an index, which affect how paths and file modes within
+    /// it are handled.
+    pub struct IndexCapabilities: u32 {
+        /// Paths are compared case-insensitively.
+        const IGNORE_CASE = raw::GIT_INDEXCAP_IGNORE_CASE as u32;
+        /// The executable bit of file modes is ignored.
+        const NO_FILEMODE = raw::GIT_INDEXCAP_NO_FILEMODE as u32;
+        /// Symbolic links are stored as plain files containing the target.
+        const NO_SYMLINKS = raw::GIT_INDEXCAP_NO_SYMLINKS as u32;
+    }
+}
+
+impl IndexCapabilities {
+    is_bit_set!(is_ignore_case, IndexCapabilities::IGNORE_CASE);
+    is_bit_set!(is_no_filemode, IndexCapabilities::NO_FILEMODE);
+    is_bit_set!(is_no_symlinks, IndexCapabilities::NO_SYMLINKS);
+}
+
 bitflags! {
     /// Flags for `Repository::open_ext`
     pub struct RepositoryOpenFlags: u32 {
@@ -556,6 +615,30 @@ impl RepositoryOpenFlags {
     is_bit_set!(is_from_env, RepositoryOpenFlags::FROM_ENV);
 }
 
+/// Where gitattributes files are looked up, such as with
+/// `Repository::attr_foreach`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttrCheckOrder {
+    /// Check the working directory, then the index.
+    FileThenIndex,
+    /// Check the index, then the working directory.
+    IndexThenFile,
+    /// Check the index only.
+    IndexOnly,
+}
+
+bitflags! {
+    /// Flags for looking up attributes, such as with `Repository::attr_foreach`
+    pub struct AttrCheckFlags: u32 {
+        /// Do not use the system gitattributes file.
+        const NO_SYSTEM = raw::GIT_ATTR_CHECK_NO_SYSTEM;
+    }
+}
+
+impl AttrCheckFlags {
+    is_bit_set!(is_no_system, AttrCheckFlags::NO_SYSTEM);
+}
+
 bitflags! {
     /// Flags for the return value of `Repository::revparse`
     pub struct RevparseMode: u32 {
@@ -634,18 +717,21 @@ pub mod build;
 pub mod cert;
 pub mod string_array;
 pub mod oid_array;
+pub mod opts;
 pub mod transport;
 
 mod blame;
 mod blob;
 mod branch;
 mod buf;
+mod cherrypick;
 mod commit;
 mod config;
 mod cred;
 mod describe;
 mod diff;
 mod error;
+mod filter;
 mod index;
 mod merge;
 mod message;
@@ -657,12 +743,15 @@ mod packbuilder;
 mod pathspec;
 mod patch;
 mod proxy_options;
+mod rebase;
+mod refdb;
 mod reference;
 mod reflog;
 mod refspec;
 mod remote;
 mod remote_callbacks;
 mod repo;
+mod revert;
 mod revspec;
 mod revwalk;
 mod signature;
@@ -673,6 +762,7 @@ mod tag;
 mod time;
 mod tree;
 mod treebuilder;
+mod worktree;
 
 fn init() {
     static INIT: Once = ONCE_INIT;
@@ -1009,6 +1099,54 @@ pub enum Delta {
     Conflicted,
 }
 
+/// The type of a line in a diff, as given by `DiffLine::origin_value`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiffLineType {
+    /// A line of context present in both files
+    Context,
+    /// A line added in the new file
+    Addition,
+    /// A line removed from the old file
+    Deletion,
+    /// Neither file has a newline at the end
+    ContextEOFNL,
+    /// The old file has no newline at the end but the new file does
+    AddEOFNL,
+    /// The old file has a newline at the end but the new file does not
+    DelEOFNL,
+    /// A file header, only produced when printing a diff
+    FileHeader,
+    /// A hunk header, only produced when printing a diff
+    HunkHeader,
+    /// A "Binary files differ" line
+    Binary,
+    /// A line with an origin not known to this library
+    Other(char),
+}
+
+impl DiffLineType {
+    /// Convert a raw git_diff_line_t to a DiffLineType.
+    pub fn from_raw(raw: raw::git_diff_line_t) -> DiffLineType {
+        match raw {
+            raw::GIT_DIFF_LINE_CONTEXT => DiffLineType::Context,
+            raw::GIT_DIFF_LINE_ADDITION => DiffLineType::Addition,
+            raw::GIT_DIFF_LINE_DELETION => DiffLineType::Deletion,
+            raw::GIT_DIFF_LINE_CONTEXT_EOFNL => DiffLineType::ContextEOFNL,
+            raw::GIT_DIFF_LINE_ADD_EOFNL => DiffLineType::AddEOFNL,
+            raw::GIT_DIFF_LINE_DEL_EOFNL => DiffLineType::DelEOFNL,
+            raw::GIT_DIFF_LINE_FILE_HDR => DiffLineType::FileHeader,
+            raw::GIT_DIFF_LINE_HUNK_HDR => DiffLineType::HunkHeader,
+            raw::GIT_DIFF_LINE_BINARY => DiffLineType::Binary,
+            n => DiffLineType::Other(n as u8 as char),
+        }
+    }
+
+    /// Convert this line type into its raw representation
+    pub fn raw(&self) -> raw::git_diff_line_t {
+        call::convert(self)
+    }
+}
+
 bitflags! {
     /// Return codes for submodule status.
     ///
@@ -1118,6 +1256,29 @@ pub enum SubmoduleIgnore {
     All,
 }
 
+/// Submodule update values
+///
+/// These values represent settings for the `submodule.$name.update`
+/// configuration value which says how to handle `git submodule update` for
+/// this submodule.
+pub enum SubmoduleUpdate {
+    /// The default; when a submodule is updated, checkout the new detached
+    /// HEAD to the submodule directory.
+    Checkout,
+    /// Update by rebasing the current checked out branch onto the commit from
+    /// the superproject.
+    Rebase,
+    /// Update by merging the commit in the superproject into the current
+    /// checkout out branch of the submodule.
+    Merge,
+    /// Do not update this submodule even when the commit in the superproject
+    /// is updated.
+    None,
+    /// Not used except as static initializer when we don't want any
+    /// particular update rule to be specified.
+    Default,
+}
+
 bitflags! {
     /// ...
     pub struct PathspecFlags: u32 {
@@ -1249,6 +1410,37 @@ pub enum FetchPrune {
     Off,
 }
 
+/// The direction in which a filter list is applied.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Convert content from the object database to the working directory
+    /// form, "smudging" it.
+    ToWorktree,
+    /// Convert content from the working directory to the object database
+    /// form, "cleaning" it.
+    ToOdb,
+}
+
+bitflags! {
+    /// Options for loading a filter list
+    pub struct FilterFlags: u32 {
+        /// Use the default behavior
+        const DEFAULT = raw::GIT_FILTER_DEFAULT as u32;
+        /// Don't error for `safecrlf` violations, allow them to continue
+        const ALLOW_UNSAFE = raw::GIT_FILTER_ALLOW_UNSAFE as u32;
+    }
+}
+
+impl FilterFlags {
+    is_bit_set!(is_allow_unsafe, FilterFlags::ALLOW_UNSAFE);
+}
+
+impl Default for FilterFlags {
+    fn default() -> Self {
+        FilterFlags::DEFAULT
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Debug)]
 pub enum StashApplyProgress {
@@ -1,6 +1,10 @@
+use std::ffi::CString;
 use std::marker;
 use std::mem;
-use libc::c_uint;
+use std::ptr;
+use std::slice;
+use std::str;
+use libc::{c_char, c_uint, c_ushort};
 
 use {raw, Oid, Commit, FileFavor};
 use util::Binding;
@@ -21,6 +25,23 @@ pub struct MergeOptions {
     raw: raw::git_merge_options,
 }
 
+/// Options to specify when merging the contents of a single file.
+pub struct MergeFileOptions {
+    ancestor_label: Option<CString>,
+    our_label: Option<CString>,
+    their_label: Option<CString>,
+    raw: raw::git_merge_file_options,
+}
+
+/// The result of merging the contents of a single file.
+///
+/// The merged contents may contain conflict markers if the merge was not
+/// automergeable.
+pub struct MergeFileResult<'repo> {
+    raw: raw::git_merge_file_result,
+    _marker: marker::PhantomData<&'repo str>,
+}
+
 impl<'repo> AnnotatedCommit<'repo> {
     /// Gets the commit ID that the given git_annotated_commit refers to
     pub fn id(&self) -> Oid {
@@ -141,6 +162,157 @@ impl MergeOptions {
     }
 }
 
+impl Default for MergeFileOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MergeFileOptions {
+    /// Creates a default set of file merge options.
+    pub fn new() -> MergeFileOptions {
+        let mut opts = MergeFileOptions {
+            ancestor_label: None,
+            our_label: None,
+            their_label: None,
+            raw: unsafe { mem::zeroed() },
+        };
+        assert_eq!(unsafe {
+            raw::git_merge_file_init_options(&mut opts.raw,
+                                     raw::GIT_MERGE_FILE_OPTIONS_VERSION)
+        }, 0);
+        opts
+    }
+
+    /// Label for the ancestor file side of the conflict which will be
+    /// prepended to labels in diff3-format merge files.
+    pub fn ancestor_label(&mut self, t: &str) -> &mut MergeFileOptions {
+        self.ancestor_label = Some(CString::new(t).unwrap());
+        self.raw.ancestor_label = label(&self.ancestor_label);
+        self
+    }
+
+    /// Label for our file side of the conflict which will be prepended to
+    /// labels in merge files.
+    pub fn our_label(&mut self, t: &str) -> &mut MergeFileOptions {
+        self.our_label = Some(CString::new(t).unwrap());
+        self.raw.our_label = label(&self.our_label);
+        self
+    }
+
+    /// Label for their file side of the conflict which will be prepended to
+    /// labels in merge files.
+    pub fn their_label(&mut self, t: &str) -> &mut MergeFileOptions {
+        self.their_label = Some(CString::new(t).unwrap());
+        self.raw.their_label = label(&self.their_label);
+        self
+    }
+
+    /// Specify a side to favor for resolving conflicts
+    pub fn favor(&mut self, favor: FileFavor) -> &mut MergeFileOptions {
+        self.raw.favor = favor.convert();
+        self
+    }
+
+    fn flag(&mut self, opt: raw::git_merge_file_flag_t, val: bool)
+            -> &mut MergeFileOptions {
+        if val {
+            self.raw.flags |= opt;
+        } else {
+            self.raw.flags &= !opt;
+        }
+        self
+    }
+
+    /// Create standard conflicted merge files
+    pub fn style_standard(&mut self, standard: bool) -> &mut MergeFileOptions {
+        self.flag(raw::GIT_MERGE_FILE_STYLE_MERGE, standard)
+    }
+
+    /// Create diff3-style file
+    pub fn style_diff3(&mut self, diff3: bool) -> &mut MergeFileOptions {
+        self.flag(raw::GIT_MERGE_FILE_STYLE_DIFF3, diff3)
+    }
+
+    /// Ignore all whitespace
+    pub fn ignore_whitespace(&mut self, ignore: bool)
+                             -> &mut MergeFileOptions {
+        self.flag(raw::GIT_MERGE_FILE_IGNORE_WHITESPACE, ignore)
+    }
+
+    /// Use the "patience diff" algorithm
+    pub fn patience(&mut self, patience: bool) -> &mut MergeFileOptions {
+        self.flag(raw::GIT_MERGE_FILE_DIFF_PATIENCE, patience)
+    }
+
+    /// The size of conflict markers (eg, "<<<<<<<"). Default is 7.
+    pub fn marker_size(&mut self, size: u16) -> &mut MergeFileOptions {
+        self.raw.marker_size = size as c_ushort;
+        self
+    }
+
+    /// Acquire a pointer to the underlying raw options.
+    ///
+    /// The returned pointer is only valid for the lifetime of these options.
+    pub unsafe fn raw(&self) -> *const raw::git_merge_file_options {
+        &self.raw as *const _
+    }
+}
+
+fn label(label: &Option<CString>) -> *const c_char {
+    label.as_ref().map(|s| s.as_ptr()).unwrap_or(ptr::null())
+}
+
+impl<'repo> MergeFileResult<'repo> {
+    /// True if the output was automerged, false if the output contains
+    /// conflict markers.
+    pub fn is_automergeable(&self) -> bool {
+        self.raw.automergeable > 0
+    }
+
+    /// The path that the resultant merge file should use, or `None` if a
+    /// filename conflict would occur or the path is not valid utf-8.
+    pub fn path(&self) -> Option<&str> {
+        self.path_bytes().and_then(|bytes| str::from_utf8(bytes).ok())
+    }
+
+    /// Gets the path as a byte slice.
+    pub fn path_bytes(&self) -> Option<&[u8]> {
+        unsafe { ::opt_bytes(self, self.raw.path) }
+    }
+
+    /// The mode that the resultant merge file should use.
+    pub fn mode(&self) -> u32 {
+        self.raw.mode as u32
+    }
+
+    /// The contents of the merge.
+    pub fn content(&self) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(self.raw.ptr as *const u8,
+                                  self.raw.len as usize)
+        }
+    }
+}
+
+/// Creates a new merge file result which takes ownership of `raw`.
+///
+/// This function is unsafe as `raw` must have been filled in by libgit2 and
+/// not freed, and is freed when the returned result is dropped.
+pub unsafe fn file_result_from_raw<'repo>(raw: raw::git_merge_file_result)
+                                          -> MergeFileResult<'repo> {
+    MergeFileResult {
+        raw: raw,
+        _marker: marker::PhantomData,
+    }
+}
+
+impl<'repo> Drop for MergeFileResult<'repo> {
+    fn drop(&mut self) {
+        unsafe { raw::git_merge_file_result_free(&mut self.raw) }
+    }
+}
+
 impl<'repo> Binding for AnnotatedCommit<'repo> {
     type Raw = *mut raw::git_annotated_commit;
     unsafe fn from_raw(raw: *mut raw::git_annotated_commit)
@@ -21,7 +21,7 @@ fn _message_prettify(message: CString, comment_char: Option<u8>)
                                             comment_char.is_some() as c_int,
                                             comment_char.unwrap_or(0) as c_char));
     }
-    Ok(ret.as_str().unwrap().to_string())
+    Ok(try!(ret.as_str()).to_string())
 }
 
 /// The default comment character for `message_prettify` ('#')
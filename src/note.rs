@@ -127,4 +127,23 @@ mod tests {
         assert_eq!(sig.name(), note_obj.committer().name());
         assert!(sig.when() == note_obj.committer().when());
     }
+
+    #[test]
+    fn overwrite() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        let notes_ref = Some("refs/notes/ci");
+        repo.note(&sig, &sig, notes_ref, head, "first", false).unwrap();
+        assert!(repo.note(&sig, &sig, notes_ref, head, "second", false)
+                    .is_err());
+        assert_eq!(repo.find_note(notes_ref, head).unwrap().message(),
+                   Some("first"));
+
+        repo.note(&sig, &sig, notes_ref, head, "second", true).unwrap();
+        assert_eq!(repo.find_note(notes_ref, head).unwrap().message(),
+                   Some("second"));
+        assert_eq!(repo.notes(notes_ref).unwrap().count(), 1);
+        assert!(repo.find_note(None, head).is_err());
+    }
 }
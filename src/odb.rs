@@ -1,13 +1,14 @@
 use std::marker;
+use std::mem;
 use std::io;
 use std::ptr;
 use std::slice;
 
 use std::ffi::CString;
 
-use libc::{c_char, c_int, c_void, size_t};
+use libc::{c_char, c_int, c_ushort, c_void, size_t};
 
-use {raw, Oid, Object, ObjectType, Error};
+use {raw, Oid, Object, ObjectType, Error, Progress};
 use panic;
 use util::Binding;
 
@@ -17,6 +18,13 @@ pub struct Odb<'repo> {
     _marker: marker::PhantomData<Object<'repo>>,
 }
 
+/// A request to expand an abbreviated object id, as used by
+/// `Odb::expand_ids`.
+#[repr(C)]
+pub struct OdbExpandId {
+    raw: raw::git_odb_expand_id,
+}
+
 impl<'repo> Binding for Odb<'repo> {
     type Raw = *mut raw::git_odb;
 
@@ -142,12 +150,28 @@ impl<'repo> Odb<'repo> {
         }
     } 
 
+    /// Expand many abbreviated object ids in a single pass over the object
+    /// database.
+    ///
+    /// Each request is updated in place. Requests that matched a unique
+    /// object of the requested type now hold its full id and actual type,
+    /// while those that were not found or were ambiguous are zeroed out.
+    pub fn expand_ids(&self, ids: &mut [OdbExpandId]) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_odb_expand_ids(self.raw,
+                                              ids.as_mut_ptr() as *mut _,
+                                              ids.len() as size_t));
+        }
+        Ok(())
+    }
+
     /// Refresh the object database.
-    /// This should never be needed, and is
-    /// provided purely for convenience.
-    /// The object database will automatically
-    /// refresh when an object is not found when
-    /// requested.
+    ///
+    /// This rescans the pack directories of the object database so that packs
+    /// written by another process become visible. The object database will
+    /// also automatically refresh when an object is not found when requested,
+    /// so this is mostly useful for long-lived handles which want to pick up
+    /// new packs eagerly.
     pub fn refresh(&self) -> Result<(), Error> {
         unsafe {
             try_call!(raw::git_odb_refresh(self.raw));
@@ -155,7 +179,52 @@ impl<'repo> Odb<'repo> {
         }
     }
 
+    /// Create a stream to write a packfile into the object database.
+    ///
+    /// The pack data written to the returned `OdbWritepack` is indexed as it
+    /// arrives, and `progress_cb` is invoked with the indexing progress.
+    /// Returning `false` from the callback aborts the write. The objects in
+    /// the pack become available once `OdbWritepack::commit` is called.
+    ///
+    /// The returned stream writes into this object database, so it cannot
+    /// outlive it:
+    ///
+    /// ```compile_fail
+    /// use git2::Repository;
+    ///
+    /// let repo = Repository::open(".").unwrap();
+    /// let writepack = {
+    ///     let odb = repo.odb().unwrap();
+    ///     odb.write_pack(|_| true).unwrap()
+    /// };
+    /// drop(writepack);
+    /// ```
+    pub fn write_pack<'odb, F>(&'odb self, progress_cb: F)
+                               -> Result<OdbWritepack<'odb>, Error>
+        where F: FnMut(Progress) -> bool + 'odb
+    {
+        let mut progress = Box::new(Box::new(progress_cb) as
+                                    Box<WritepackProgressCb<'odb>>);
+        let mut out = ptr::null_mut();
+        unsafe {
+            let payload = &mut *progress as *mut _ as *mut c_void;
+            let cb: raw::git_transfer_progress_cb = writepack_progress_cb;
+            try_call!(raw::git_odb_write_pack(&mut out, self.raw, Some(cb),
+                                              payload));
+            Ok(OdbWritepack {
+                raw: out,
+                stats: mem::zeroed(),
+                _progress: progress,
+                _marker: marker::PhantomData,
+            })
+        }
+    }
+
     /// Adds an alternate disk backend to the object database.
+    ///
+    /// `path` is the `objects` directory of another repository. Objects found
+    /// there become readable through this database, but new objects are never
+    /// written to it.
     pub fn add_disk_alternate(&self, path: &str) -> Result<(), Error> {
         unsafe {
             let path = try!(CString::new(path));
@@ -312,6 +381,61 @@ impl<'repo> io::Write for OdbWriter<'repo> {
     fn flush(&mut self) -> io::Result<()> { Ok(()) }
 }
 
+type WritepackProgressCb<'a> = FnMut(Progress) -> bool + 'a;
+
+/// A stream to write a packfile into the object database, created with
+/// `Odb::write_pack`.
+pub struct OdbWritepack<'repo> {
+    raw: *mut raw::git_odb_writepack,
+    stats: raw::git_transfer_progress,
+    _progress: Box<Box<WritepackProgressCb<'repo>>>,
+    _marker: marker::PhantomData<Object<'repo>>,
+}
+
+impl<'repo> OdbWritepack<'repo> {
+    /// Finish writing the packfile, making the objects it contains available
+    /// in the object database.
+    pub fn commit(mut self) -> Result<(), Error> {
+        unsafe {
+            let rc = ((*self.raw).commit)(self.raw, &mut self.stats);
+            try!(::call::try(rc));
+        }
+        Ok(())
+    }
+}
+
+impl<'repo> Drop for OdbWritepack<'repo> {
+    fn drop(&mut self) {
+        unsafe { ((*self.raw).free)(self.raw) }
+    }
+}
+
+impl<'repo> io::Write for OdbWritepack<'repo> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        unsafe {
+            let ptr = buf.as_ptr() as *const c_void;
+            let len = buf.len();
+            let res = ((*self.raw).append)(self.raw, ptr, len,
+                                           &mut self.stats);
+            if res < 0 {
+                Err(io::Error::new(io::ErrorKind::Other, "Write error"))
+            } else {
+                Ok(buf.len())
+            }
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+extern fn writepack_progress_cb(stats: *const raw::git_transfer_progress,
+                                payload: *mut c_void) -> c_int {
+    let ok = panic::wrap(|| unsafe {
+        let callback = &mut *(payload as *mut Box<WritepackProgressCb>);
+        callback(Binding::from_raw(stats))
+    });
+    if ok == Some(true) {0} else {-1}
+}
+
 pub type ForeachCb<'a> = FnMut(&Oid) -> bool + 'a;
 
 struct ForeachCbData<'a> {
@@ -333,11 +457,45 @@ extern fn foreach_cb(id: *const raw::git_oid,
     }).unwrap_or(1)
 }
 
+impl OdbExpandId {
+    /// Create a request to expand the first `len` hex characters of `id`.
+    ///
+    /// If `kind` is given, only objects of that type will match.
+    pub fn new(id: Oid, len: usize, kind: Option<ObjectType>) -> OdbExpandId {
+        OdbExpandId {
+            raw: raw::git_odb_expand_id {
+                id: unsafe { *id.raw() },
+                length: len as c_ushort,
+                kind: kind.unwrap_or(ObjectType::Any).raw(),
+            },
+        }
+    }
+
+    /// The id of the object, which is the full id once expanded or the zero
+    /// id if no unique object matched.
+    pub fn id(&self) -> Oid {
+        unsafe { Binding::from_raw(&self.raw.id as *const _) }
+    }
+
+    /// The number of significant hex characters of the id.
+    ///
+    /// This is 40 once the id has been expanded, and 0 if it was not found.
+    pub fn length(&self) -> usize {
+        self.raw.length as usize
+    }
+
+    /// The type of the object, or `None` if it was not found.
+    pub fn kind(&self) -> Option<ObjectType> {
+        ObjectType::from_raw(self.raw.kind)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use std::io::prelude::*;
     use tempdir::TempDir;
-    use {Repository, ObjectType, Oid};
+    use {Buf, Repository, ObjectType, Oid, OdbExpandId};
 
     #[test]
     fn read() {
@@ -404,6 +562,68 @@ mod tests {
         assert!(db.exists(id));
     }
 
+    #[test]
+    fn refresh() {
+        let (_td, repo) = ::test::repo_init();
+        let db = repo.odb().unwrap();
+
+        let (td2, src) = ::test::repo_init();
+        let id = src.blob(b"packed").unwrap();
+        {
+            let mut builder = src.treebuilder(None).unwrap();
+            builder.insert("file", id, 0o100644).unwrap();
+            let tree = src.find_tree(builder.write().unwrap()).unwrap();
+            let head = src.head().unwrap().peel_to_commit().unwrap();
+            let sig = src.signature().unwrap();
+            src.commit(Some("HEAD"), &sig, &sig, "packed", &tree,
+                       &[&head]).unwrap();
+        }
+        assert!(!db.exists(id));
+
+        // A fetch over `file://` stores the objects it receives in a pack.
+        let td3 = TempDir::new("test").unwrap();
+        let url = ::test::path2url(td2.path());
+        let clone = Repository::clone(&url, td3.path()).unwrap();
+        let pack_dir = repo.path().join("objects/pack");
+        for entry in fs::read_dir(clone.path().join("objects/pack")).unwrap() {
+            let path = entry.unwrap().path();
+            fs::copy(&path, pack_dir.join(path.file_name().unwrap())).unwrap();
+        }
+
+        // libgit2 also rescans the pack directory when a lookup misses, so
+        // this only checks that an explicit refresh succeeds and leaves the
+        // new pack readable, not that the refresh is what made it visible.
+        db.refresh().unwrap();
+        assert!(db.exists(id));
+        assert_eq!(db.read(id).unwrap().data(), b"packed");
+    }
+
+    #[test]
+    fn write_pack() {
+        let (_td, repo) = ::test::repo_init();
+        let (_td2, src) = ::test::repo_init();
+        let id = src.blob(b"from a pack").unwrap();
+        let mut pack = Buf::new();
+        {
+            let mut builder = src.packbuilder().unwrap();
+            builder.insert_object(id, None).unwrap();
+            builder.write_buf(&mut pack).unwrap();
+        }
+
+        let db = repo.odb().unwrap();
+        assert!(!db.exists(id));
+        let mut progressed = false;
+        {
+            let mut writer = db.write_pack(|_| { progressed = true; true })
+                               .unwrap();
+            writer.write_all(&pack).unwrap();
+            writer.commit().unwrap();
+        }
+        assert!(progressed);
+        assert_eq!(db.read(id).unwrap().data(), b"from a pack");
+        assert_eq!(repo.find_blob(id).unwrap().content(), b"from a pack");
+    }
+
     #[test]
     fn exists_prefix() {
         let td = TempDir::new("test").unwrap();
@@ -416,4 +636,48 @@ mod tests {
         let found_oid = db.exists_prefix(id_prefix, 10).unwrap();
         assert_eq!(found_oid, id);
     }
+
+    #[test]
+    fn add_disk_alternate() {
+        let td = TempDir::new("test").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        let td2 = TempDir::new("test").unwrap();
+        let other = Repository::init(td2.path()).unwrap();
+        let id = other.blob(b"only in other").unwrap();
+
+        let db = repo.odb().unwrap();
+        assert!(!db.exists(id));
+        let objects = other.path().join("objects");
+        db.add_disk_alternate(objects.to_str().unwrap()).unwrap();
+        assert!(db.exists(id));
+        assert_eq!(repo.find_blob(id).unwrap().content(), b"only in other");
+    }
+
+    #[test]
+    fn expand_ids() {
+        let td = TempDir::new("test").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        let db = repo.odb().unwrap();
+        let ids = (0..5).map(|i| {
+            db.write(ObjectType::Blob, format!("blob {}", i).as_bytes())
+                .unwrap()
+        }).collect::<Vec<_>>();
+        let short = |id: &Oid| Oid::from_str(&id.to_string()[..7]).unwrap();
+
+        let mut requests = ids.iter().map(|id| {
+            OdbExpandId::new(short(id), 7, None)
+        }).collect::<Vec<_>>();
+        requests.push(OdbExpandId::new(short(&ids[0]), 7,
+                                       Some(ObjectType::Commit)));
+        db.expand_ids(&mut requests).unwrap();
+
+        for (request, id) in requests.iter().zip(&ids) {
+            assert_eq!(request.id(), *id);
+            assert_eq!(request.length(), 40);
+            assert_eq!(request.kind(), Some(ObjectType::Blob));
+        }
+        let mismatch = requests.last().unwrap();
+        assert!(mismatch.id().is_zero());
+        assert_eq!(mismatch.length(), 0);
+    }
 }
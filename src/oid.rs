@@ -210,5 +210,21 @@ mod tests {
         file.write_all("Hello".as_bytes()).unwrap();
         assert!(Oid::hash_file(ObjectType::Blob, &path).is_ok());
     }
+
+    #[test]
+    fn hash_matches_git() {
+        let expected = "b6fc4c620b67d95f953a5c1c1230aaab5db5a1b0";
+        let id = Oid::hash_object(ObjectType::Blob, b"hello").unwrap();
+        assert_eq!(id.to_string(), expected);
+
+        let td = TempDir::new("test").unwrap();
+        let path = td.path().join("hello.txt");
+        File::create(&path).unwrap().write_all(b"hello").unwrap();
+        assert_eq!(Oid::hash_file(ObjectType::Blob, &path).unwrap(), id);
+
+        let (_td, repo) = ::test::repo_init();
+        assert!(!repo.odb().unwrap().exists(id));
+        assert_eq!(repo.blob(b"hello").unwrap(), id);
+    }
 }
 
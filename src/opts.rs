@@ -0,0 +1,223 @@
+//! Bindings to libgit2's global options.
+//!
+//! These options affect every repository opened by the process, as they are
+//! stored in libgit2's global state.
+
+use std::ffi::CString;
+use std::path::Path;
+
+use libc::{c_char, c_int, size_t, ssize_t};
+
+use {raw, Buf, Error, ObjectType};
+use util::Binding;
+
+/// Set the maximum amount of memory that can be held by libgit2's object
+/// cache, shared between all repositories.
+///
+/// Objects are evicted from the cache once this limit is exceeded. The
+/// default is 256MB.
+pub fn set_cache_max_size(bytes: usize) -> Result<(), Error> {
+    ::init();
+    unsafe {
+        try!(::call::try(raw::git_libgit2_opts(
+            raw::GIT_OPT_SET_CACHE_MAX_SIZE as c_int,
+            bytes as ssize_t)));
+    }
+    Ok(())
+}
+
+/// Set the maximum size of an object of the given type for it to be
+/// considered eligible for caching.
+///
+/// Setting a limit of 0 disables caching of that type of object. By default
+/// blobs are not cached, while commits, trees and tags up to 4KB are.
+pub fn set_cache_object_limit(kind: ObjectType, size: usize)
+                              -> Result<(), Error> {
+    ::init();
+    unsafe {
+        try!(::call::try(raw::git_libgit2_opts(
+            raw::GIT_OPT_SET_CACHE_OBJECT_LIMIT as c_int,
+            kind.raw() as c_int,
+            size)));
+    }
+    Ok(())
+}
+
+/// Get the amount of memory currently held by the object cache and the
+/// maximum that it is allowed to hold, in bytes.
+pub fn cached_memory() -> Result<(usize, usize), Error> {
+    ::init();
+    let mut current: ssize_t = 0;
+    let mut allowed: ssize_t = 0;
+    unsafe {
+        try!(::call::try(raw::git_libgit2_opts(
+            raw::GIT_OPT_GET_CACHED_MEMORY as c_int,
+            &mut current as *mut ssize_t,
+            &mut allowed as *mut ssize_t)));
+    }
+    Ok((current as usize, allowed as usize))
+}
+
+/// Get the size of the windows libgit2 maps packfiles into memory with.
+pub fn mwindow_size() -> Result<usize, Error> {
+    get_size(raw::GIT_OPT_GET_MWINDOW_SIZE)
+}
+
+/// Set the size of the windows libgit2 maps packfiles into memory with.
+///
+/// The default is 1GB on 64-bit platforms and 32MB on 32-bit platforms.
+pub fn set_mwindow_size(bytes: usize) -> Result<(), Error> {
+    set_size(raw::GIT_OPT_SET_MWINDOW_SIZE, bytes)
+}
+
+/// Get the maximum amount of packfile data libgit2 keeps mapped into memory
+/// at any one time.
+pub fn mwindow_mapped_limit() -> Result<usize, Error> {
+    get_size(raw::GIT_OPT_GET_MWINDOW_MAPPED_LIMIT)
+}
+
+/// Set the maximum amount of packfile data libgit2 keeps mapped into memory
+/// at any one time.
+///
+/// Once the limit is reached, the least recently used windows are unmapped.
+/// The default is 8GB on 64-bit platforms and 256MB on 32-bit platforms.
+pub fn set_mwindow_mapped_limit(bytes: usize) -> Result<(), Error> {
+    set_size(raw::GIT_OPT_SET_MWINDOW_MAPPED_LIMIT, bytes)
+}
+
+/// Get the value of the User-Agent header libgit2 sends over HTTP.
+///
+/// An empty string is returned if no custom user agent has been set, and an
+/// error if the user agent is not valid utf-8.
+pub fn user_agent() -> Result<String, Error> {
+    ::init();
+    let buf = Buf::new();
+    unsafe {
+        try!(::call::try(raw::git_libgit2_opts(
+            raw::GIT_OPT_GET_USER_AGENT as c_int,
+            buf.raw())));
+    }
+    Ok(try!(buf.as_str()).to_string())
+}
+
+/// Set the value of the User-Agent header libgit2 sends over HTTP.
+///
+/// The header is sent as `git/2.0 (<user agent>)`, as some servers only
+/// recognize git clients by the `git/` prefix.
+pub fn set_user_agent(user_agent: &str) -> Result<(), Error> {
+    ::init();
+    let user_agent = try!(CString::new(user_agent));
+    unsafe {
+        try!(::call::try(raw::git_libgit2_opts(
+            raw::GIT_OPT_SET_USER_AGENT as c_int,
+            user_agent.as_ptr())));
+    }
+    Ok(())
+}
+
+/// Set the locations libgit2 loads trusted SSL certificates from.
+///
+/// `file` is a file containing concatenated certificates in PEM format and
+/// `dir` is a directory of hashed certificates, as used by OpenSSL's
+/// `SSL_CTX_load_verify_locations`. At least one of them should be given.
+///
+/// This is only supported when libgit2 is built with OpenSSL, and an error
+/// is returned for other TLS backends.
+pub fn set_ssl_cert_locations(file: Option<&Path>, dir: Option<&Path>)
+                              -> Result<(), Error> {
+    ::init();
+    let file = try!(::opt_cstr(file));
+    let dir = try!(::opt_cstr(dir));
+    let file: *const c_char = ::call::convert(&file);
+    let dir: *const c_char = ::call::convert(&dir);
+    unsafe {
+        try!(::call::try(raw::git_libgit2_opts(
+            raw::GIT_OPT_SET_SSL_CERT_LOCATIONS as c_int,
+            file,
+            dir)));
+    }
+    Ok(())
+}
+
+fn get_size(opt: raw::git_libgit2_opt_t) -> Result<usize, Error> {
+    ::init();
+    let mut size: size_t = 0;
+    unsafe {
+        try!(::call::try(raw::git_libgit2_opts(opt as c_int,
+                                               &mut size as *mut size_t)));
+    }
+    Ok(size as usize)
+}
+
+fn set_size(opt: raw::git_libgit2_opt_t, size: usize) -> Result<(), Error> {
+    ::init();
+    unsafe {
+        try!(::call::try(raw::git_libgit2_opts(opt as c_int,
+                                               size as size_t)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use ObjectType;
+    use super::{cached_memory, set_cache_max_size, set_cache_object_limit};
+    use super::{mwindow_size, set_mwindow_size};
+    use super::{mwindow_mapped_limit, set_mwindow_mapped_limit};
+    use super::{set_ssl_cert_locations, set_user_agent, user_agent};
+
+    #[test]
+    fn cache() {
+        let (_td, repo) = ::test::repo_init();
+        let (_, prev) = cached_memory().unwrap();
+
+        set_cache_max_size(1024 * 1024).unwrap();
+        set_cache_object_limit(ObjectType::Blob, 1024).unwrap();
+        let id = repo.blob(b"cached").unwrap();
+        for _ in 0..10 {
+            repo.find_blob(id).unwrap();
+            repo.head().unwrap().peel_to_commit().unwrap().tree().unwrap();
+        }
+        let (current, allowed) = cached_memory().unwrap();
+        assert_eq!(allowed, 1024 * 1024);
+        assert!(current <= allowed);
+
+        assert!(set_cache_object_limit(ObjectType::Any, 1024).is_err());
+        set_cache_object_limit(ObjectType::Blob, 0).unwrap();
+        set_cache_max_size(prev).unwrap();
+    }
+
+    #[test]
+    fn mwindow() {
+        let size = mwindow_size().unwrap();
+        assert!(size > 0);
+        set_mwindow_size(size * 2).unwrap();
+        assert_eq!(mwindow_size().unwrap(), size * 2);
+        set_mwindow_size(size).unwrap();
+
+        let limit = mwindow_mapped_limit().unwrap();
+        assert!(limit > 0);
+        set_mwindow_mapped_limit(limit * 2).unwrap();
+        assert_eq!(mwindow_mapped_limit().unwrap(), limit * 2);
+        set_mwindow_mapped_limit(limit).unwrap();
+    }
+
+    #[test]
+    fn user_agent_roundtrip() {
+        set_user_agent("git2-rs-test").unwrap();
+        assert_eq!(user_agent().unwrap(), "git2-rs-test");
+        assert!(set_user_agent("a\0b").is_err());
+    }
+
+    #[test]
+    fn ssl_cert_locations() {
+        let td = TempDir::new("certs").unwrap();
+        let res = set_ssl_cert_locations(None, Some(td.path()));
+        if cfg!(all(feature = "https", unix, not(target_os = "macos"))) {
+            res.unwrap();
+        } else {
+            assert!(res.is_err());
+        }
+    }
+}
@@ -193,10 +193,64 @@ impl Patch {
 
     /// Get the Patch text as a Buf.
     pub fn to_buf(&mut self) -> Result<Buf, Error> {
-        let buf = Buf::new();
+        let mut buf = Buf::new();
+        try!(self.to_buf_into(&mut buf));
+        Ok(buf)
+    }
+
+    /// Append the Patch text to an existing Buf.
+    ///
+    /// The text is appended to any contents already in `buf`, so call
+    /// `Buf::clear` first to reuse a buffer across calls.
+    pub fn to_buf_into(&mut self, buf: &mut Buf) -> Result<(), Error> {
         unsafe {
             try_call!(raw::git_patch_to_buf(buf.raw(), self.raw));
         }
-        Ok(buf)
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use Patch;
+
+    #[test]
+    fn size() {
+        let path = Path::new("foo");
+        let mut patch = t!(Patch::from_buffers(b"a\nb\nc\n", Some(path),
+                                               b"a\nd\nc\n", Some(path),
+                                               None));
+        let buf = t!(patch.to_buf());
+        let content = patch.size(false, false, false);
+        let full = patch.size(true, true, true);
+        assert!(content > 0);
+        assert!(content < full);
+        assert!(patch.size(true, true, false) <= buf.len());
+        assert_eq!(full, buf.len());
+    }
+
+    #[test]
+    fn line_in_hunk() {
+        let path = Path::new("foo");
+        let mut patch = t!(Patch::from_buffers(b"a\nb\nc\n", Some(path),
+                                               b"a\nd\nc\n", Some(path),
+                                               None));
+        assert_eq!(patch.num_hunks(), 1);
+        assert_eq!(t!(patch.num_lines_in_hunk(0)), 4);
+        {
+            let line = t!(patch.line_in_hunk(0, 1));
+            assert_eq!(line.origin(), '-');
+            assert_eq!(line.content(), b"b\n");
+            assert_eq!(line.old_lineno(), Some(2));
+            assert_eq!(line.new_lineno(), None);
+        }
+        {
+            let line = t!(patch.line_in_hunk(0, 2));
+            assert_eq!(line.origin(), '+');
+            assert_eq!(line.content(), b"d\n");
+        }
+        assert!(patch.line_in_hunk(0, 4).is_err());
+        assert!(patch.num_lines_in_hunk(1).is_err());
     }
 }
@@ -0,0 +1,403 @@
+use std::ffi::CString;
+use std::{marker, mem, ptr, str};
+
+use build::CheckoutBuilder;
+use util::Binding;
+use {raw, Error, Index, MergeOptions, Oid, Signature};
+
+/// Rebase options
+///
+/// Use to tell the rebase machinery how to operate.
+pub struct RebaseOptions<'cb> {
+    raw: raw::git_rebase_options,
+    merge_options: Option<MergeOptions>,
+    checkout_options: Option<CheckoutBuilder<'cb>>,
+    rewrite_notes_ref: Option<CString>,
+}
+
+impl<'cb> Default for RebaseOptions<'cb> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'cb> RebaseOptions<'cb> {
+    /// Creates a new default set of rebase options.
+    pub fn new() -> RebaseOptions<'cb> {
+        let mut opts = RebaseOptions {
+            raw: unsafe { mem::zeroed() },
+            merge_options: None,
+            checkout_options: None,
+            rewrite_notes_ref: None,
+        };
+        assert_eq!(unsafe {
+            raw::git_rebase_init_options(&mut opts.raw,
+                                         raw::GIT_REBASE_OPTIONS_VERSION)
+        }, 0);
+        opts
+    }
+
+    /// Used by `Repository::rebase`, this will instruct other clients working
+    /// on this rebase that you want a quiet rebase experience, which they may
+    /// choose to provide in an application-specific manner. This has no
+    /// effect upon libgit2 directly, but is provided for interoperability
+    /// between Git tools.
+    pub fn quiet(&mut self, quiet: bool) -> &mut RebaseOptions<'cb> {
+        self.raw.quiet = quiet as i32;
+        self
+    }
+
+    /// Used by `Repository::rebase`, this will begin an in-memory rebase,
+    /// which will allow callers to step through the rebase operations and
+    /// commit the rebased changes, but will not rewind HEAD or update the
+    /// repository to be in a rebasing state. This will not interfere with
+    /// the working directory (if there is one).
+    pub fn inmemory(&mut self, inmemory: bool) -> &mut RebaseOptions<'cb> {
+        self.raw.inmemory = inmemory as i32;
+        self
+    }
+
+    /// Used by `Rebase::finish`, this is the name of the notes reference
+    /// used to rewrite notes for rebased commits when finishing the rebase;
+    /// if not provided, the contents of the configuration option
+    /// `notes.rewriteRef` is examined, unless the configuration option
+    /// `notes.rewrite.rebase` is set to false.
+    pub fn rewrite_notes_ref(&mut self, rewrite_notes_ref: &str)
+                             -> &mut RebaseOptions<'cb> {
+        self.rewrite_notes_ref = Some(CString::new(rewrite_notes_ref).unwrap());
+        self
+    }
+
+    /// Options to control how trees are merged during `Rebase::next`.
+    pub fn merge_options(&mut self, opts: MergeOptions)
+                         -> &mut RebaseOptions<'cb> {
+        self.merge_options = Some(opts);
+        self
+    }
+
+    /// Options to control how files are written during `Repository::rebase`,
+    /// `Rebase::next` and `Rebase::abort`. Note that a minimum strategy of
+    /// `safe` is defaulted in `init` and `next`, and a minimum strategy of
+    /// `force` is defaulted in `abort` to match git semantics.
+    pub fn checkout_options(&mut self, opts: CheckoutBuilder<'cb>)
+                            -> &mut RebaseOptions<'cb> {
+        self.checkout_options = Some(opts);
+        self
+    }
+
+    /// Acquire a pointer to the underlying raw options.
+    ///
+    /// The returned pointer is only valid for the lifetime of these options.
+    pub fn raw(&mut self) -> *const raw::git_rebase_options {
+        unsafe {
+            if let Some(ref opts) = self.merge_options {
+                ptr::copy(opts.raw(), &mut self.raw.merge_options, 1);
+            }
+            if let Some(ref mut opts) = self.checkout_options {
+                opts.configure(&mut self.raw.checkout_options);
+            }
+            self.raw.rewrite_notes_ref = self.rewrite_notes_ref.as_ref()
+                .map(|s| s.as_ptr())
+                .unwrap_or(ptr::null());
+        }
+        &self.raw
+    }
+}
+
+/// Representation of a rebase
+pub struct Rebase<'repo> {
+    raw: *mut raw::git_rebase,
+    _marker: marker::PhantomData<&'repo raw::git_rebase>,
+}
+
+impl<'repo> Rebase<'repo> {
+    /// Gets the count of rebase operations that are to be applied.
+    pub fn len(&self) -> usize {
+        unsafe { raw::git_rebase_operation_entrycount(self.raw) }
+    }
+
+    /// Returns `true` if there are no rebase operations to apply.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the index of the rebase operation that is currently being
+    /// applied, or `None` if `next` has not yet been called.
+    pub fn operation_current(&mut self) -> Option<usize> {
+        let cur = unsafe { raw::git_rebase_operation_current(self.raw) };
+        if cur == raw::GIT_REBASE_NO_OPERATION {
+            None
+        } else {
+            Some(cur)
+        }
+    }
+
+    /// Gets the rebase operation specified by the given index.
+    pub fn nth(&mut self, n: usize) -> Option<RebaseOperation> {
+        unsafe {
+            let op = raw::git_rebase_operation_byindex(self.raw, n);
+            if op.is_null() {
+                None
+            } else {
+                Some(RebaseOperation::from_raw(op))
+            }
+        }
+    }
+
+    /// Gets the index produced by the last operation, which is the result of
+    /// `next` and which will be committed by the next invocation of
+    /// `commit`. This is useful for resolving conflicts in an in-memory
+    /// rebase before committing them.
+    ///
+    /// This is only applicable for in-memory rebases; for rebases within a
+    /// working directory, the changes were applied to the repository's
+    /// index.
+    pub fn inmemory_index(&mut self) -> Result<Index, Error> {
+        let mut idx = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_rebase_inmemory_index(&mut idx, self.raw));
+            Ok(Binding::from_raw(idx))
+        }
+    }
+
+    /// Commits the current patch. You must have resolved any conflicts that
+    /// were introduced during the patch application from the `next`
+    /// invocation.
+    ///
+    /// If `author` is `None` the author of the original commit is kept, and
+    /// if `message` is `None` the original commit message is used.
+    pub fn commit(&mut self,
+                  author: Option<&Signature>,
+                  committer: &Signature,
+                  message: Option<&str>)
+                  -> Result<Oid, Error> {
+        let mut id: raw::git_oid = unsafe { mem::zeroed() };
+        let message = try!(::opt_cstr(message));
+        unsafe {
+            try_call!(raw::git_rebase_commit(&mut id,
+                                             self.raw,
+                                             author.map(|a| a.raw()),
+                                             committer.raw(),
+                                             ptr::null(),
+                                             message));
+            Ok(Binding::from_raw(&id as *const _))
+        }
+    }
+
+    /// Aborts a rebase that is currently in progress, resetting the
+    /// repository and working directory to their state before rebase began.
+    pub fn abort(&mut self) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_rebase_abort(self.raw));
+        }
+        Ok(())
+    }
+
+    /// Finishes a rebase that is currently in progress once all patches have
+    /// been applied.
+    ///
+    /// The `signature` is the identity used when rewriting notes for the
+    /// rebased commits; if `None` the repository's default signature is
+    /// used.
+    pub fn finish(&mut self, signature: Option<&Signature>)
+                  -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_rebase_finish(self.raw,
+                                             signature.map(|s| s.raw())));
+        }
+        Ok(())
+    }
+}
+
+impl<'rebase> Iterator for Rebase<'rebase> {
+    type Item = Result<RebaseOperation<'rebase>, Error>;
+
+    /// Performs the next rebase operation and returns the information about
+    /// it. If the operation is one that applies a patch (which is any
+    /// operation except `Exec`) then the patch will be applied and the index
+    /// and working directory will be updated with the changes. If there are
+    /// conflicts, you will need to address those before committing the
+    /// changes.
+    fn next(&mut self) -> Option<Result<RebaseOperation<'rebase>, Error>> {
+        let mut out = ptr::null_mut();
+        unsafe {
+            try_call_iter!(raw::git_rebase_next(&mut out, self.raw));
+            Some(Ok(RebaseOperation::from_raw(out)))
+        }
+    }
+}
+
+impl<'repo> Binding for Rebase<'repo> {
+    type Raw = *mut raw::git_rebase;
+    unsafe fn from_raw(raw: *mut raw::git_rebase) -> Rebase<'repo> {
+        Rebase {
+            raw: raw,
+            _marker: marker::PhantomData,
+        }
+    }
+    fn raw(&self) -> *mut raw::git_rebase { self.raw }
+}
+
+impl<'repo> Drop for Rebase<'repo> {
+    fn drop(&mut self) {
+        unsafe { raw::git_rebase_free(self.raw) }
+    }
+}
+
+/// A rebase operation
+///
+/// Describes a single instruction/operation to be performed during the
+/// rebase.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RebaseOperationType {
+    /// The given commit is to be cherry-picked. The client should commit the
+    /// changes and continue if there are no conflicts.
+    Pick,
+
+    /// The given commit is to be cherry-picked, but the client should prompt
+    /// the user to provide an updated commit message.
+    Reword,
+
+    /// The given commit is to be cherry-picked, but the client should stop to
+    /// allow the user to edit the changes before committing them.
+    Edit,
+
+    /// The given commit is to be squashed into the previous commit. The
+    /// commit message will be merged with the previous message.
+    Squash,
+
+    /// The given commit is to be squashed into the previous commit. The
+    /// commit message from this commit will be discarded.
+    Fixup,
+
+    /// No commit will be cherry-picked. The client should run the given
+    /// command and (if successful) continue.
+    Exec,
+}
+
+impl RebaseOperationType {
+    /// Convert from the int into an enum. Returns None if invalid.
+    pub fn from_raw(raw: raw::git_rebase_operation_t)
+                    -> Option<RebaseOperationType> {
+        match raw {
+            raw::GIT_REBASE_OPERATION_PICK => Some(RebaseOperationType::Pick),
+            raw::GIT_REBASE_OPERATION_REWORD => {
+                Some(RebaseOperationType::Reword)
+            }
+            raw::GIT_REBASE_OPERATION_EDIT => Some(RebaseOperationType::Edit),
+            raw::GIT_REBASE_OPERATION_SQUASH => {
+                Some(RebaseOperationType::Squash)
+            }
+            raw::GIT_REBASE_OPERATION_FIXUP => {
+                Some(RebaseOperationType::Fixup)
+            }
+            raw::GIT_REBASE_OPERATION_EXEC => Some(RebaseOperationType::Exec),
+            _ => None,
+        }
+    }
+}
+
+/// A rebase operation
+///
+/// Describes a single instruction/operation to be performed during the
+/// rebase.
+pub struct RebaseOperation<'rebase> {
+    raw: *const raw::git_rebase_operation,
+    _marker: marker::PhantomData<Rebase<'rebase>>,
+}
+
+impl<'rebase> RebaseOperation<'rebase> {
+    /// The type of rebase operation
+    pub fn kind(&self) -> Option<RebaseOperationType> {
+        unsafe { RebaseOperationType::from_raw((*self.raw).kind) }
+    }
+
+    /// The commit ID being cherry-picked. This will be populated for all
+    /// operations except those of type `Exec`.
+    pub fn id(&self) -> Oid {
+        unsafe { Binding::from_raw(&(*self.raw).id as *const _) }
+    }
+
+    /// The executable the user has requested be run. This will only be
+    /// populated for operations of type `Exec`.
+    pub fn exec(&self) -> Option<&str> {
+        unsafe {
+            ::opt_bytes(self, (*self.raw).exec).and_then(|s| {
+                str::from_utf8(s).ok()
+            })
+        }
+    }
+}
+
+impl<'rebase> Binding for RebaseOperation<'rebase> {
+    type Raw = *const raw::git_rebase_operation;
+    unsafe fn from_raw(raw: *const raw::git_rebase_operation)
+                       -> RebaseOperation<'rebase> {
+        RebaseOperation {
+            raw: raw,
+            _marker: marker::PhantomData,
+        }
+    }
+    fn raw(&self) -> *const raw::git_rebase_operation { self.raw }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Oid, RebaseOptions, RebaseOperationType, ResetType};
+
+    #[test]
+    fn smoke() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let commit = |refname: &str, files: &[&str], parent: Oid, msg: &str| {
+            let mut builder = repo.treebuilder(None).unwrap();
+            for file in files {
+                let blob = repo.blob(file.as_bytes()).unwrap();
+                builder.insert(file, blob, 0o100644).unwrap();
+            }
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+            let parent = repo.find_commit(parent).unwrap();
+            repo.commit(Some(refname), &sig, &sig, msg, &tree,
+                        &[&parent]).unwrap()
+        };
+        let base = repo.head().unwrap().target().unwrap();
+        let master = commit("HEAD", &["master"], base, "master");
+        let f1 = commit("refs/heads/feature", &["f1"], base, "f1");
+        let f2 = commit("refs/heads/feature", &["f1", "f2"], f1, "f2");
+        let obj = repo.find_object(master, None).unwrap();
+        repo.reset(&obj, ResetType::Hard, None).unwrap();
+
+        let feature = repo.find_reference("refs/heads/feature").unwrap();
+        let branch = repo.reference_to_annotated_commit(&feature).unwrap();
+        let upstream = repo.find_annotated_commit(master).unwrap();
+        let mut opts = RebaseOptions::new();
+        opts.quiet(true);
+        let mut rebase = repo.rebase(Some(&branch), Some(&upstream), None,
+                                     Some(&mut opts)).unwrap();
+        assert_eq!(rebase.len(), 2);
+        assert_eq!(rebase.operation_current(), None);
+        assert_eq!(rebase.nth(1).unwrap().id(), f2);
+
+        let mut picked = Vec::new();
+        while let Some(op) = rebase.next() {
+            let op = op.unwrap();
+            assert_eq!(op.kind(), Some(RebaseOperationType::Pick));
+            assert!(op.exec().is_none());
+            picked.push(op.id());
+            rebase.commit(None, &sig, None).unwrap();
+        }
+        assert_eq!(picked, [f1, f2]);
+        assert_eq!(rebase.operation_current(), Some(1));
+        rebase.finish(None).unwrap();
+
+        let feature = repo.find_reference("refs/heads/feature").unwrap();
+        let tip = feature.peel_to_commit().unwrap();
+        assert_eq!(tip.message(), Some("f2"));
+        assert_eq!(tip.parent(0).unwrap().message(), Some("f1"));
+        assert_eq!(tip.parent(0).unwrap().parent_id(0).unwrap(), master);
+        let tree = tip.tree().unwrap();
+        for file in &["master", "f1", "f2"] {
+            assert!(tree.get_name(file).is_some());
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::marker;
+
+use {raw, Error, Repository};
+use util::Binding;
+
+/// A structure to represent the reference database of a repository.
+pub struct Refdb<'repo> {
+    raw: *mut raw::git_refdb,
+    _marker: marker::PhantomData<&'repo Repository>,
+}
+
+impl<'repo> Refdb<'repo> {
+    /// Suggest that the backends of this reference database compress or
+    /// optimize their references.
+    ///
+    /// For the default filesystem backend this packs loose references into
+    /// the packed-refs file.
+    pub fn compress(&self) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_refdb_compress(self.raw));
+        }
+        Ok(())
+    }
+}
+
+impl<'repo> Binding for Refdb<'repo> {
+    type Raw = *mut raw::git_refdb;
+
+    unsafe fn from_raw(raw: *mut raw::git_refdb) -> Refdb<'repo> {
+        Refdb {
+            raw: raw,
+            _marker: marker::PhantomData,
+        }
+    }
+    fn raw(&self) -> *mut raw::git_refdb { self.raw }
+}
+
+impl<'repo> Drop for Refdb<'repo> {
+    fn drop(&mut self) {
+        unsafe { raw::git_refdb_free(self.raw) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn compress() {
+        let (td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/heads/foo", head, false, "create").unwrap();
+        assert!(td.path().join(".git/refs/heads/foo").exists());
+
+        let refdb = repo.refdb().unwrap();
+        refdb.compress().unwrap();
+        assert!(!td.path().join(".git/refs/heads/foo").exists());
+        assert!(td.path().join(".git/packed-refs").exists());
+        let r = repo.find_reference("refs/heads/foo").unwrap();
+        assert_eq!(r.target(), Some(head));
+    }
+}
@@ -30,6 +30,12 @@ pub struct ReferenceNames<'repo: 'references, 'references> {
     inner: &'references mut References<'repo>,
 }
 
+/// An iterator over the references in a repository paired with the ids of
+/// the objects they peel to.
+pub struct PeeledReferences<'repo> {
+    inner: References<'repo>,
+}
+
 impl<'repo> Reference<'repo> {
     /// Ensure the reference name is well-formed.
     pub fn is_valid_name(refname: &str) -> bool {
@@ -236,6 +242,19 @@ impl<'repo> Reference<'repo> {
         }
     }
 
+    /// Create an independent copy of this reference.
+    ///
+    /// The copy is a separate in-memory handle for the same reference; it
+    /// doesn't look up the reference again, so it reflects the state of this
+    /// handle rather than the reference database.
+    pub fn dup(&self) -> Result<Reference<'repo>, Error> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_reference_dup(&mut raw, self.raw));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
 }
 
 impl<'repo> PartialOrd for Reference<'repo> {
@@ -287,6 +306,19 @@ impl<'repo> References<'repo> {
     pub fn names<'a>(&'a mut self) -> ReferenceNames<'repo, 'a> {
         ReferenceNames { inner: self }
     }
+
+    /// Consumes a `References` iterator to create an iterator over the name
+    /// of each reference and the id of the object it peels to.
+    ///
+    /// Where the reference database already knows the peeled target of a
+    /// reference, as is the case for annotated tags stored in the
+    /// `packed-refs` file, that id is used directly rather than reading the
+    /// tag object from the object database.
+    ///
+    /// A reference whose name is not valid utf-8 is yielded as an error.
+    pub fn peeled(self) -> PeeledReferences<'repo> {
+        PeeledReferences { inner: self }
+    }
 }
 
 impl<'repo> Binding for References<'repo> {
@@ -329,8 +361,32 @@ impl<'repo, 'references> Iterator for ReferenceNames<'repo, 'references> {
     }
 }
 
+impl<'repo> Iterator for PeeledReferences<'repo> {
+    type Item = Result<(String, Oid), Error>;
+    fn next(&mut self) -> Option<Result<(String, Oid), Error>> {
+        let reference = match self.inner.next() {
+            Some(Ok(reference)) => reference,
+            Some(Err(e)) => return Some(Err(e)),
+            None => return None,
+        };
+        let id = match reference.target_peel() {
+            Some(id) => id,
+            None => match reference.peel(ObjectType::Any) {
+                Ok(object) => object.id(),
+                Err(e) => return Some(Err(e)),
+            },
+        };
+        match str::from_utf8(reference.name_bytes()) {
+            Ok(name) => Some(Ok((name.to_string(), id))),
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::fs::OpenOptions;
+    use std::io::prelude::*;
     use {Reference, ObjectType, ReferenceType};
 
     #[test]
@@ -339,6 +395,80 @@ mod tests {
         assert!(!Reference::is_valid_name("foo"));
     }
 
+    #[test]
+    fn dup() {
+        let (_td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap();
+        let target = head.target().unwrap();
+        let copy = head.dup().unwrap();
+        drop(head);
+
+        assert_eq!(copy.name(), Some("refs/heads/master"));
+        assert_eq!(copy.target(), Some(target));
+        assert_eq!(copy.peel_to_commit().unwrap().id(), target);
+        assert!(copy == repo.find_reference("refs/heads/master").unwrap());
+    }
+
+    #[test]
+    fn peeled() {
+        let (_td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+        let sig = repo.signature().unwrap();
+        let obj = repo.find_object(id, None).unwrap();
+        for i in 0..20 {
+            repo.tag(&format!("annotated{}", i), &obj, &sig, "msg", false)
+                .unwrap();
+            repo.tag_lightweight(&format!("light{}", i), &obj, false).unwrap();
+        }
+        repo.refdb().unwrap().compress().unwrap();
+
+        let tag = repo.find_reference("refs/tags/annotated0").unwrap();
+        assert_eq!(tag.target_peel(), Some(id));
+
+        let mut count = 0;
+        for item in repo.references_peeled().unwrap() {
+            let (name, peeled) = item.unwrap();
+            let reference = repo.find_reference(&name).unwrap();
+            let expected = reference.peel(ObjectType::Any).unwrap().id();
+            assert_eq!(peeled, expected, "{}", name);
+            assert_eq!(peeled, id);
+            count += 1;
+        }
+        assert_eq!(count, 41);
+
+        // A name that is not utf-8 is an error rather than being mangled.
+        let packed = repo.path().join("packed-refs");
+        let mut file = OpenOptions::new().append(true).open(packed).unwrap();
+        file.write_all(format!("{} refs/heads/", id).as_bytes()).unwrap();
+        file.write_all(b"\xff\n").unwrap();
+        drop(file);
+        let items = repo.references_peeled().unwrap().collect::<Vec<_>>();
+        assert_eq!(items.len(), 42);
+        assert_eq!(items.iter().filter(|item| item.is_err()).count(), 1);
+    }
+
+    #[test]
+    fn classify() {
+        let (_td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+        let sig = repo.signature().unwrap();
+        let obj = repo.find_object(id, None).unwrap();
+        repo.tag("v1", &obj, &sig, "v1", false).unwrap();
+        repo.note(&sig, &sig, None, id, "note", false).unwrap();
+        repo.reference("refs/remotes/origin/master", id, false, "test")
+            .unwrap();
+
+        let check = |name: &str, tag: bool, remote: bool, note: bool| {
+            let r = repo.find_reference(name).unwrap();
+            assert_eq!((r.is_tag(), r.is_remote(), r.is_note()),
+                       (tag, remote, note), "{}", name);
+            assert!(!r.is_branch());
+        };
+        check("refs/tags/v1", true, false, false);
+        check("refs/remotes/origin/master", false, true, false);
+        check("refs/notes/commits", false, false, true);
+    }
+
     #[test]
     fn smoke2() {
         let (_td, repo) = ::test::repo_init();
@@ -398,4 +528,60 @@ mod tests {
         head.delete().unwrap();
 
     }
+
+    #[test]
+    fn glob() {
+        let (_td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/heads/a", id, false, "test").unwrap();
+        repo.reference("refs/heads/b", id, false, "test").unwrap();
+        repo.reference("refs/tags/t1", id, false, "test").unwrap();
+        repo.reference("refs/tags/t2", id, false, "test").unwrap();
+
+        let mut names = repo.references_glob("refs/tags/*").unwrap()
+            .map(|r| r.unwrap().name().unwrap().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["refs/tags/t1", "refs/tags/t2"]);
+        assert_eq!(repo.references_glob("refs/heads/*").unwrap().count(), 3);
+    }
+
+    #[test]
+    fn names() {
+        let (_td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+        for name in ["refs/heads/a", "refs/remotes/o/b", "refs/tags/c"].iter() {
+            repo.reference(name, id, false, "test").unwrap();
+        }
+
+        let mut full = repo.references().unwrap()
+            .map(|r| r.unwrap().name().unwrap().to_string())
+            .collect::<Vec<_>>();
+        let mut names = repo.references().unwrap();
+        let mut names = names.names()
+            .map(|r| r.unwrap().to_string())
+            .collect::<Vec<_>>();
+        full.sort();
+        names.sort();
+        assert_eq!(names.len(), 4);
+        assert_eq!(names, full);
+    }
+
+    #[test]
+    fn shorthand() {
+        let (_td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+        let cases = [
+            ("refs/heads/feature/x", "feature/x"),
+            ("refs/remotes/origin/master", "origin/master"),
+            ("refs/tags/v1.0", "v1.0"),
+            ("refs/notes/commits", "notes/commits"),
+            ("refs/custom/thing", "custom/thing"),
+        ];
+        for &(name, short) in cases.iter() {
+            let r = repo.reference(name, id, false, "test").unwrap();
+            assert_eq!(r.shorthand(), Some(short));
+            assert_eq!(r.shorthand_bytes(), short.as_bytes());
+        }
+    }
 }
@@ -113,6 +113,9 @@ impl<'repo> Remote<'repo> {
     }
 
     /// Open a connection to a remote.
+    ///
+    /// No callbacks are installed for this connection, so transports which
+    /// need credentials will fail to connect. Use `connect_auth` for those.
     pub fn connect(&mut self, dir: Direction) -> Result<(), Error> {
         // TODO: can callbacks be exposed safely?
         unsafe {
@@ -126,20 +129,38 @@ impl<'repo> Remote<'repo> {
 
     /// Open a connection to a remote with callbacks and proxy settings
     ///
+    /// The callbacks, including any `credentials` callback, stay alive for as
+    /// long as the connection so they are also used by `list`.
+    ///
     /// Returns a `RemoteConnection` that will disconnect once dropped
     pub fn connect_auth<'connection, 'cb>(&'connection mut self,
                                           dir: Direction,
                                           cb: Option<RemoteCallbacks<'cb>>,
                                           proxy_options: Option<ProxyOptions<'cb>>)
                     -> Result<RemoteConnection<'repo, 'connection, 'cb>, Error> {
+        self.connect_auth_with_headers(dir, cb, proxy_options, &[])
+    }
 
+    /// Open a connection to a remote like `connect_auth`, also sending the
+    /// given extra HTTP headers, such as `"X-Custom: value"`.
+    ///
+    /// The headers are only used by the HTTP(S) transports.
+    pub fn connect_auth_with_headers<'connection, 'cb>(
+        &'connection mut self,
+        dir: Direction,
+        cb: Option<RemoteCallbacks<'cb>>,
+        proxy_options: Option<ProxyOptions<'cb>>,
+        custom_headers: &[&str])
+        -> Result<RemoteConnection<'repo, 'connection, 'cb>, Error>
+    {
         let cb = Box::new(cb.unwrap_or_else(RemoteCallbacks::new));
         let proxy_options = proxy_options.unwrap_or_else(ProxyOptions::new);
+        let (_a, _b, headers) = try!(::util::iter2cstrs(custom_headers.iter()));
         unsafe {
             try_call!(raw::git_remote_connect(self.raw, dir,
                                               &cb.raw(),
                                               &proxy_options.raw(),
-                                              ptr::null()));
+                                              &headers));
         }
 
         Ok(RemoteConnection {
@@ -179,7 +200,8 @@ impl<'repo> Remote<'repo> {
         Ok(())
     }
 
-    /// Get the number of refspecs for a remote
+    /// Get an iterator over the refspecs configured for this remote, both
+    /// fetch and push.
     pub fn refspecs<'a>(&'a self) -> Refspecs<'a> {
         let cnt = unsafe { raw::git_remote_refspec_count(&*self.raw) as usize };
         Refspecs { range: 0..cnt, remote: self }
@@ -227,6 +249,11 @@ impl<'repo> Remote<'repo> {
     }
 
     /// Update the tips to the new state
+    ///
+    /// This is typically called after `download` to update the
+    /// remote-tracking branches. `download_tags` controls which tags are
+    /// auto-followed, `update_fetchhead` controls whether `FETCH_HEAD` is
+    /// written, and `msg` is used for the reflog of any updated references.
     pub fn update_tips(&mut self,
                        callbacks: Option<&mut RemoteCallbacks>,
                        update_fetchhead: bool,
@@ -262,6 +289,9 @@ impl<'repo> Remote<'repo> {
     }
 
     /// Get the statistics structure that is filled in by the fetch operation.
+    ///
+    /// This can be used to report on a transfer after it has completed, for
+    /// example the number of objects and bytes received by `fetch`.
     pub fn stats(&self) -> Progress {
         unsafe {
             Binding::from_raw(raw::git_remote_stats(self.raw))
@@ -545,9 +575,12 @@ impl<'repo, 'connection, 'cb> Drop for RemoteConnection<'repo, 'connection, 'cb>
 #[cfg(test)]
 mod tests {
     use std::cell::Cell;
+    use std::io::prelude::*;
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
     use tempdir::TempDir;
     use {Repository, Remote, RemoteCallbacks, Direction, FetchOptions};
-    use {AutotagOption, PushOptions};
+    use {AutotagOption, Cred, CredentialType, ProxyOptions, PushOptions};
 
     #[test]
     fn smoke() {
@@ -635,6 +668,55 @@ mod tests {
         t!(repo.remote_add_fetch("origin", "bar"));
     }
 
+    #[test]
+    fn refspecs() {
+        let (_td, repo) = ::test::repo_init();
+        repo.remote("origin", "/path/to/nowhere").unwrap();
+        t!(repo.remote_add_fetch("origin", "refs/tags/*:refs/tags/*"));
+
+        let origin = repo.find_remote("origin").unwrap();
+        let specs = origin.refspecs().collect::<Vec<_>>();
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].str(), Some("+refs/heads/*:refs/remotes/origin/*"));
+        assert!(specs[0].is_force());
+        assert_eq!(specs[1].src(), Some("refs/tags/*"));
+        assert_eq!(specs[1].dst(), Some("refs/tags/*"));
+        assert!(!specs[1].is_force());
+        assert!(specs.iter().all(|s| match s.direction() {
+            Direction::Fetch => true,
+            Direction::Push => false,
+        }));
+        assert_eq!(origin.refspecs().len(), 2);
+    }
+
+    #[test]
+    fn add_refspecs_persist() {
+        let (_td, repo) = ::test::repo_init();
+        repo.remote("origin", "/path/to/nowhere").unwrap();
+        t!(repo.remote_add_fetch("origin", "+refs/pull/*:refs/remotes/pr/*"));
+        t!(repo.remote_add_push("origin", "refs/heads/master"));
+
+        let cfg = repo.config().unwrap();
+        let fetch: Vec<String> = cfg.entries(Some("remote\\.origin\\.fetch"))
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.unwrap().value().unwrap().into())
+            .collect();
+        assert_eq!(fetch, ["+refs/heads/*:refs/remotes/origin/*",
+                           "+refs/pull/*:refs/remotes/pr/*"]);
+        assert_eq!(cfg.get_string("remote.origin.push").unwrap(),
+                   "refs/heads/master");
+
+        let origin = repo.find_remote("origin").unwrap();
+        let fetch = origin.fetch_refspecs().unwrap();
+        assert_eq!(fetch.iter().collect::<Vec<_>>(),
+                   [Some("+refs/heads/*:refs/remotes/origin/*"),
+                    Some("+refs/pull/*:refs/remotes/pr/*")]);
+        let push = origin.push_refspecs().unwrap();
+        assert_eq!(push.iter().collect::<Vec<_>>(), [Some("refs/heads/master")]);
+        assert_eq!(origin.refspecs().count(), 3);
+    }
+
     #[test]
     fn rename_remote() {
         let (_td, repo) = ::test::repo_init();
@@ -689,6 +771,120 @@ mod tests {
         assert!(progress_hit.get());
     }
 
+    #[test]
+    fn fetch_stats() {
+        let (td, _repo) = ::test::repo_init();
+        let td2 = TempDir::new("git").unwrap();
+        let url = ::test::path2url(&td.path());
+
+        let repo = Repository::init(td2.path()).unwrap();
+        let mut origin = repo.remote("origin", &url).unwrap();
+        assert_eq!(origin.stats().received_objects(), 0);
+        t!(origin.fetch(&[], None, None));
+
+        let stats = origin.stats();
+        assert!(stats.received_objects() > 0);
+        assert!(stats.received_bytes() > 0);
+        assert_eq!(stats.received_objects(), stats.total_objects());
+        assert_eq!(stats.indexed_objects(), stats.total_objects());
+    }
+
+    #[test]
+    fn fetch_deltas() {
+        let (td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let mut contents = String::new();
+        for i in 0..200 {
+            contents.push_str(&format!("line number {}\n", i));
+        }
+        for _ in 0..2 {
+            contents.push_str("one more line\n");
+            let tree = ::test::single_file_tree(&repo, contents.as_bytes());
+            let head = t!(t!(repo.head()).peel_to_commit());
+            t!(repo.commit(Some("HEAD"), &sig, &sig, "update", &tree,
+                           &[&head]));
+        }
+
+        let td2 = TempDir::new("git").unwrap();
+        let url = ::test::path2url(&td.path());
+        let repo = Repository::init(td2.path()).unwrap();
+        let mut origin = repo.remote("origin", &url).unwrap();
+        let mut total_deltas = 0;
+        {
+            let mut callbacks = RemoteCallbacks::new();
+            callbacks.transfer_progress(|progress| {
+                assert!(progress.indexed_deltas() <= progress.total_deltas());
+                total_deltas = progress.total_deltas();
+                true
+            });
+            let mut options = FetchOptions::new();
+            options.remote_callbacks(callbacks);
+            t!(origin.fetch(&[], Some(&mut options), None));
+        }
+        assert!(total_deltas > 0);
+
+        let stats = origin.stats();
+        assert!(stats.total_deltas() > 0);
+        assert_eq!(stats.indexed_deltas(), stats.total_deltas());
+    }
+
+    #[test]
+    fn download_then_update_tips() {
+        let (td, remote_repo) = ::test::repo_init();
+        let head = t!(remote_repo.refname_to_id("HEAD"));
+        let td2 = TempDir::new("git").unwrap();
+        let url = ::test::path2url(&td.path());
+
+        let repo = Repository::init(td2.path()).unwrap();
+        let mut origin = repo.remote("origin", &url).unwrap();
+        t!(origin.download(&[], None));
+        assert!(repo.find_reference("refs/remotes/origin/master").is_err());
+        assert!(!repo.path().join("FETCH_HEAD").exists());
+
+        let updated = Cell::new(0);
+        {
+            let mut callbacks = RemoteCallbacks::new();
+            callbacks.update_tips(|name, old, new| {
+                assert_eq!(name, "refs/remotes/origin/master");
+                assert!(old.is_zero());
+                assert_eq!(new, head);
+                updated.set(updated.get() + 1);
+                true
+            });
+            t!(origin.update_tips(Some(&mut callbacks), true,
+                                  AutotagOption::Auto, Some("tips")));
+        }
+        assert_eq!(updated.get(), 1);
+        assert_eq!(t!(repo.refname_to_id("refs/remotes/origin/master")),
+                   head);
+        assert!(repo.path().join("FETCH_HEAD").exists());
+        let reflog = t!(repo.reflog("refs/remotes/origin/master"));
+        assert_eq!(reflog.get(0).unwrap().message(), Some("tips"));
+    }
+
+    #[test]
+    fn download_refspec() {
+        let (td, remote_repo) = ::test::repo_init();
+        let other = {
+            let head = t!(t!(remote_repo.head()).peel_to_commit());
+            let sig = t!(remote_repo.signature());
+            let tree = t!(head.tree());
+            t!(remote_repo.commit(Some("refs/heads/other"), &sig, &sig,
+                                  "other", &tree, &[&head]))
+        };
+        let td2 = TempDir::new("git").unwrap();
+        let url = ::test::path2url(&td.path());
+
+        let repo = Repository::init(td2.path()).unwrap();
+        let mut origin = repo.remote("origin", &url).unwrap();
+        assert!(!t!(repo.odb()).exists(other));
+        t!(origin.download(&["refs/heads/other:refs/remotes/origin/other"],
+                           None));
+        assert!(t!(repo.odb()).exists(other));
+        assert!(repo.find_reference("refs/remotes/origin/other").is_err());
+        assert!(repo.find_reference("refs/remotes/origin/master").is_err());
+    }
+
     /// This test is meant to assure that the callbacks provided to connect will not cause
     /// segfaults
     #[test]
@@ -720,6 +916,83 @@ mod tests {
         assert!(!origin.connected());
     }
 
+    #[test]
+    fn connect_auth_credentials() {
+        // A minimal smart HTTP server which asks for credentials and, once
+        // they are given, advertises a single branch.
+        fn pkt(s: &str) -> String { format!("{:04x}{}", s.len() + 4, s) }
+        fn read_request(stream: &mut TcpStream) -> Option<String> {
+            let mut request = Vec::new();
+            let mut byte = [0];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut byte) {
+                    Ok(1) => request.push(byte[0]),
+                    _ => return None,
+                }
+            }
+            Some(String::from_utf8_lossy(&request).to_lowercase())
+        }
+
+        let listener = t!(TcpListener::bind("127.0.0.1:0"));
+        let url = format!("http://{}/repo", t!(listener.local_addr()));
+        let oid = "0123456789abcdef0123456789abcdef01234567";
+        let server = thread::spawn(move || {
+            let mut headers = true;
+            loop {
+                let (mut stream, _) = t!(listener.accept());
+                let mut authorized = false;
+                while let Some(request) = read_request(&mut stream) {
+                    headers = headers && request.contains("x-git2-test: yes");
+                    if !request.contains("authorization: basic ") {
+                        t!(stream.write_all(b"HTTP/1.1 401 Unauthorized\r\n\
+                            WWW-Authenticate: Basic realm=\"git2\"\r\n\
+                            Content-Length: 0\r\n\r\n"));
+                        continue
+                    }
+                    authorized = true;
+                    let body = format!("{}0000{}{}0000",
+                        pkt("# service=git-upload-pack\n"),
+                        pkt(&format!("{} HEAD\0\n", oid)),
+                        pkt(&format!("{} refs/heads/master\n", oid)));
+                    t!(write!(stream, "HTTP/1.1 200 OK\r\n\
+                        Content-Type: \
+                        application/x-git-upload-pack-advertisement\r\n\
+                        Content-Length: {}\r\n\r\n{}", body.len(), body));
+                }
+                if authorized {
+                    return headers
+                }
+            }
+        });
+
+        let td = TempDir::new("git").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        let asked = Cell::new(0);
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|_url, _username, allowed| {
+            asked.set(asked.get() + 1);
+            assert!(allowed.contains(CredentialType::USER_PASS_PLAINTEXT));
+            if asked.get() > 1 {
+                return Err(::Error::from_str("credentials rejected"))
+            }
+            Cred::userpass_plaintext("user", "pass")
+        });
+
+        let mut origin = repo.remote("origin", &url).unwrap();
+        {
+            let connection = t!(origin.connect_auth_with_headers(
+                Direction::Fetch, Some(callbacks), Some(ProxyOptions::new()),
+                &["X-Git2-Test: yes"]));
+            let list = t!(connection.list());
+            assert_eq!(list.len(), 2);
+            assert_eq!(list[1].name(), "refs/heads/master");
+            assert_eq!(list[1].oid().to_string(), oid);
+        }
+        assert!(!origin.connected());
+        assert_eq!(asked.get(), 1);
+        assert!(server.join().unwrap());
+    }
+
     #[test]
     fn push() {
         let (_td, repo) = ::test::repo_init();
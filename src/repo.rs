@@ -8,12 +8,19 @@ use std::str;
 use libc::{c_int, c_char, size_t, c_void, c_uint};
 
 use {raw, Revspec, Error, init, Object, RepositoryOpenFlags, RepositoryState, Remote, Buf, StashFlags};
+use PeeledReferences;
+use panic;
 use {ResetType, Signature, Reference, References, Submodule, Blame, BlameOptions};
 use {Branches, BranchType, Index, Config, Oid, Blob, BlobWriter, Branch, Commit, Tree};
 use {AnnotatedCommit, MergeOptions, SubmoduleIgnore, SubmoduleStatus, MergeAnalysis, MergePreference};
+use SubmoduleUpdate;
 use {ObjectType, Tag, Note, Notes, StatusOptions, Statuses, Status, Revwalk};
 use {RevparseMode, RepositoryInitMode, Reflog, IntoCString, Describe};
 use {DescribeOptions, TreeBuilder, Diff, DiffOptions, PackBuilder, Odb};
+use {AttrCheckFlags, AttrCheckOrder, ErrorCode, Refdb, Worktree};
+use {FilterFlags, FilterList, FilterMode};
+use {CherrypickOptions, RevertOptions, Rebase, RebaseOptions};
+use {IndexEntry, MergeFileOptions, MergeFileResult};
 use build::{RepoBuilder, CheckoutBuilder};
 use stash::{StashApplyOptions, StashCbData, stash_cb};
 use string_array::StringArray;
@@ -48,6 +55,21 @@ pub struct RepositoryInitOptions {
     origin_url: Option<CString>,
 }
 
+/// A description of a commit to be created by `Repository::commit_batch`.
+pub struct CommitSpec<'a> {
+    author: &'a Signature<'a>,
+    committer: &'a Signature<'a>,
+    message: &'a str,
+    tree: Oid,
+    parents: Vec<CommitParent>,
+}
+
+#[derive(Copy, Clone)]
+enum CommitParent {
+    Id(Oid),
+    Batch(usize),
+}
+
 impl Repository {
     /// Attempt to open an already-existing repository at `path`.
     ///
@@ -155,6 +177,30 @@ impl Repository {
         Repository::open(util::bytes2path(&*buf))
     }
 
+    /// Attempt to open an already-existing repository at or above `path`,
+    /// stopping the search at any of the given ceiling directories.
+    ///
+    /// This behaves like `discover`, except that the lookup will not walk up
+    /// into any of `ceiling_dirs`, and it will only cross filesystem
+    /// boundaries if `across_fs` is `true`.
+    pub fn discover_ceiling<P, O, I>(path: P,
+                                     ceiling_dirs: I,
+                                     across_fs: bool)
+                                     -> Result<Repository, Error>
+            where P: AsRef<Path>, O: AsRef<OsStr>, I: IntoIterator<Item=O>
+    {
+        init();
+        let buf = Buf::new();
+        let path = try!(path.as_ref().into_c_string());
+        let ceiling_dirs_os = try!(env::join_paths(ceiling_dirs));
+        let ceiling_dirs = try!(ceiling_dirs_os.into_c_string());
+        unsafe {
+            try_call!(raw::git_repository_discover(buf.raw(), path, across_fs,
+                                                   ceiling_dirs));
+        }
+        Repository::open(util::bytes2path(&*buf))
+    }
+
     /// Creates a new repository in the specified folder.
     ///
     /// This by default will create any necessary directories to create the
@@ -324,6 +370,19 @@ impl Repository {
         }
     }
 
+    /// Returns the path to the common `.git` folder shared by all worktrees
+    /// of this repository.
+    ///
+    /// For a linked worktree `path` returns the worktree's own gitdir, while
+    /// this returns the gitdir of the main repository where objects and most
+    /// references live. For other repositories this is the same as `path`.
+    pub fn commondir(&self) -> Option<&Path> {
+        unsafe {
+            let ptr = raw::git_repository_commondir(self.raw);
+            ::opt_bytes(self, ptr).map(util::bytes2path)
+        }
+    }
+
     /// Returns the current state of this repository
     pub fn state(&self) -> RepositoryState {
         let state = unsafe { raw::git_repository_state(self.raw) };
@@ -397,6 +456,11 @@ impl Repository {
     }
 
     /// Set the active namespace for this repository.
+    ///
+    /// While a namespace is active, references are read from and written to
+    /// `refs/namespaces/<namespace>/` instead of the top-level `refs/`. The
+    /// namespace is picked up when the reference database is first loaded,
+    /// so it should be set before any references are accessed.
     pub fn set_namespace(&self, namespace: &str) -> Result<(), Error> {
         self.set_namespace_bytes(namespace.as_bytes())
     }
@@ -430,11 +494,94 @@ impl Repository {
         }
     }
 
+    /// Invoke `callback` for each entry in the repository's MERGE_HEAD file,
+    /// that is for each commit being merged into HEAD.
+    ///
+    /// Return `true` from the callback to continue iterating or `false` to
+    /// stop.
+    pub fn mergehead_foreach<C>(&self, mut callback: C) -> Result<(), Error>
+        where C: FnMut(&Oid) -> bool
+    {
+        unsafe {
+            let mut data = MergeheadForeachCbData { callback: &mut callback };
+            try_call!(raw::git_repository_mergehead_foreach(self.raw(),
+                                                mergehead_foreach_cb,
+                                                &mut data as *mut _ as *mut _));
+            Ok(())
+        }
+    }
+
+    /// Invoke `callback` for each entry in the repository's FETCH_HEAD file.
+    ///
+    /// The callback receives the name of the fetched reference, the URL of
+    /// the remote it was fetched from, its target and whether it was marked
+    /// for merging. The name is `None` for entries which were fetched without
+    /// naming a reference, such as with `git fetch <url> <sha>`, and either
+    /// value may not be valid utf-8. Return `true` from the callback to
+    /// continue iterating or `false` to stop.
+    pub fn fetchhead_foreach<C>(&self, mut callback: C) -> Result<(), Error>
+        where C: FnMut(Option<&[u8]>, Option<&[u8]>, &Oid, bool) -> bool
+    {
+        unsafe {
+            let mut data = FetchheadForeachCbData { callback: &mut callback };
+            try_call!(raw::git_repository_fetchhead_foreach(self.raw(),
+                                                fetchhead_foreach_cb,
+                                                &mut data as *mut _ as *mut _));
+            Ok(())
+        }
+    }
+
+    /// Invoke `callback` for each gitattribute that applies to `path`.
+    ///
+    /// The path is relative to the working directory. `order` controls
+    /// whether gitattributes files are read from the working directory, the
+    /// index or both, and `flags` can further restrict which files are
+    /// read. The callback receives
+    /// the name and raw value of each attribute. Attributes that are set
+    /// (`attr`) or unset (`-attr`) have libgit2's internal `[internal]__TRUE__`
+    /// and `[internal]__FALSE__` values, and attributes that are made
+    /// unspecified (`!attr`) have an empty value. Attributes whose name is
+    /// not valid utf-8 are skipped.
+    ///
+    /// Return `true` from the callback to continue iterating or `false` to
+    /// stop.
+    pub fn attr_foreach<C>(&self,
+                           order: AttrCheckOrder,
+                           flags: AttrCheckFlags,
+                           path: &Path,
+                           mut callback: C) -> Result<(), Error>
+        where C: FnMut(&str, &[u8]) -> bool
+    {
+        let path = try!(path.into_c_string());
+        let flags = ::call::convert::<u32, _>(&order) | flags.bits();
+        unsafe {
+            let mut data = AttrForeachCbData { callback: &mut callback };
+            try_call!(raw::git_attr_foreach(self.raw(), flags, path,
+                                            attr_foreach_cb,
+                                            &mut data as *mut _ as *mut _));
+            Ok(())
+        }
+    }
+
+    /// Tests whether the repository has a prepared merge message, as read by
+    /// `message`.
+    pub fn has_message(&self) -> bool {
+        self.path().join("MERGE_MSG").exists()
+    }
+
     /// Remove the Git merge message.
+    ///
+    /// This does nothing if there is no merge message to remove.
     pub fn remove_message(&self) -> Result<(), Error> {
         unsafe {
-            try_call!(raw::git_repository_message_remove(self.raw));
-            Ok(())
+            match ::call::try(raw::git_repository_message_remove(self.raw)) {
+                Ok(_) => Ok(()),
+                // libgit2 reports a missing file from `unlink` as a generic
+                // error, so only fail if a message is still left behind.
+                Err(ref e) if e.code() == ErrorCode::NotFound ||
+                              !self.has_message() => Ok(()),
+                Err(e) => Err(e),
+            }
         }
     }
 
@@ -450,6 +597,31 @@ impl Repository {
         }
     }
 
+    /// List the names of the linked worktrees of this repository.
+    pub fn worktrees(&self) -> Result<StringArray, Error> {
+        let mut arr = raw::git_strarray {
+            strings: 0 as *mut *mut c_char,
+            count: 0,
+        };
+        unsafe {
+            try_call!(raw::git_worktree_list(&mut arr, self.raw));
+            Ok(Binding::from_raw(arr))
+        }
+    }
+
+    /// Look up a linked worktree of this repository by name.
+    ///
+    /// The name is the one given when the worktree was created, which is also
+    /// the name of its directory under `.git/worktrees`.
+    pub fn find_worktree(&self, name: &str) -> Result<Worktree, Error> {
+        let mut ret = ptr::null_mut();
+        let name = try!(CString::new(name));
+        unsafe {
+            try_call!(raw::git_worktree_lookup(&mut ret, self.raw, name));
+            Ok(Binding::from_raw(ret))
+        }
+    }
+
     /// Get the information for a particular remote
     pub fn find_remote(&self, name: &str) -> Result<Remote, Error> {
         let mut ret = ptr::null_mut();
@@ -598,11 +770,15 @@ impl Repository {
     /// to a commit.
     ///
     /// The `checkout` options will only be used for a hard reset.
+    ///
+    /// As with `git reset`, the commit HEAD pointed to beforehand is recorded
+    /// in `ORIG_HEAD`, which can be read back with `orig_head`.
     pub fn reset(&self,
                  target: &Object,
                  kind: ResetType,
                  checkout: Option<&mut CheckoutBuilder>)
                  -> Result<(), Error> {
+        let head = self.refname_to_id("HEAD").ok();
         unsafe {
             let mut opts: raw::git_checkout_options = mem::zeroed();
             try_call!(raw::git_checkout_init_options(&mut opts,
@@ -612,9 +788,37 @@ impl Repository {
             });
             try_call!(raw::git_reset(self.raw, target.raw(), kind, opts));
         }
+        if let Some(head) = head {
+            try!(self.reference("ORIG_HEAD", head, true,
+                                "reset: updating ORIG_HEAD"));
+        }
         Ok(())
     }
 
+    /// Get the commit that HEAD pointed to before the last reset or merge.
+    ///
+    /// This reads the `ORIG_HEAD` reference, returning an error with the code
+    /// `ErrorCode::NotFound` if it does not exist.
+    pub fn orig_head(&self) -> Result<Oid, Error> {
+        self.refname_to_id("ORIG_HEAD")
+    }
+
+    /// Get the commit currently being cherry-picked.
+    ///
+    /// This reads the `CHERRY_PICK_HEAD` reference, returning an error with
+    /// the code `ErrorCode::NotFound` if no cherry-pick is in progress.
+    pub fn cherrypick_head(&self) -> Result<Oid, Error> {
+        self.refname_to_id("CHERRY_PICK_HEAD")
+    }
+
+    /// Get the commit currently being reverted.
+    ///
+    /// This reads the `REVERT_HEAD` reference, returning an error with the
+    /// code `ErrorCode::NotFound` if no revert is in progress.
+    pub fn revert_head(&self) -> Result<Oid, Error> {
+        self.refname_to_id("REVERT_HEAD")
+    }
+
     /// Updates some entries in the index from the target commit tree.
     ///
     /// The scope of the updated entries is determined by the paths being
@@ -656,7 +860,19 @@ impl Repository {
     ///
     /// Otherwise, the HEAD will be detached and will directly point to the
     /// commit.
+    ///
+    /// The reflog entry written for HEAD uses git's "checkout: moving from
+    /// X to Y" message. To record a custom message instead, update HEAD with
+    /// `reference_symbolic` or `reference`.
     pub fn set_head(&self, refname: &str) -> Result<(), Error> {
+        self.set_head_bytes(refname.as_bytes())
+    }
+
+    /// Make the repository HEAD point to the specified reference as a byte
+    /// array.
+    ///
+    /// See `set_head` for more information.
+    pub fn set_head_bytes(&self, refname: &[u8]) -> Result<(), Error> {
         let refname = try!(CString::new(refname));
         unsafe {
             try_call!(raw::git_repository_set_head(self.raw, refname));
@@ -703,6 +919,14 @@ impl Repository {
         }
     }
 
+    /// Create an iterator over the names of the repo's references and the ids
+    /// of the objects they peel to.
+    ///
+    /// See `References::peeled` for more information.
+    pub fn references_peeled(&self) -> Result<PeeledReferences, Error> {
+        self.references().map(|r| r.peeled())
+    }
+
     /// Create an iterator for the repo's references that match the specified
     /// glob
     pub fn references_glob(&self, glob: &str) -> Result<References, Error> {
@@ -854,6 +1078,20 @@ impl Repository {
         }
     }
 
+    /// Get a read-only snapshot of the configuration for this repository.
+    ///
+    /// Unlike `config`, values read from the returned configuration won't
+    /// change if the configuration files are modified in the meantime, which
+    /// gives a consistent view when looking up several related values.
+    pub fn config_snapshot(&self) -> Result<Config, Error> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_repository_config_snapshot(&mut raw,
+                                                          self.raw()));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
     /// Write an in-memory buffer to the ODB as a blob.
     ///
     /// The Oid returned can in turn be passed to `find_blob` to get a handle to
@@ -872,6 +1110,12 @@ impl Repository {
     /// Read a file from the filesystem and write its content to the Object
     /// Database as a loose blob
     ///
+    /// If the file is inside the working directory, the clean filters that
+    /// apply to its path (such as `core.autocrlf` line ending conversion) are
+    /// run on the content first, as `git add` would. Files outside of the
+    /// working directory, which may be given by absolute path, are stored
+    /// as-is without any filtering.
+    ///
     /// The Oid returned can in turn be passed to `find_blob` to get a handle to
     /// the blob.
     pub fn blob_path(&self, path: &Path) -> Result<Oid, Error> {
@@ -920,6 +1164,30 @@ impl Repository {
         }
     }
 
+    /// Load the filter list for a path in this repository.
+    ///
+    /// The filters which apply depend on the attributes and configuration for
+    /// `path`, which is relative to the working directory. If a `blob` is
+    /// provided it may be used by some filters to decide how the content is
+    /// handled.
+    ///
+    /// Returns `None` if no filters need to be applied for the given path.
+    pub fn filter_list_load(&self,
+                            blob: Option<&Blob>,
+                            path: &Path,
+                            mode: FilterMode,
+                            flags: FilterFlags)
+                            -> Result<Option<FilterList>, Error> {
+        let path = try!(path.into_c_string());
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_filter_list_load(&mut raw, self.raw(),
+                                                blob.map(|b| b.raw()),
+                                                path, mode, flags.bits()));
+            Ok(Binding::from_raw_opt(raw))
+        }
+    }
+
     /// Get the object database for this repository
     pub fn odb(&self) -> Result<Odb, Error> {
         let mut odb = ptr::null_mut();
@@ -929,6 +1197,31 @@ impl Repository {
         }
     }
 
+    /// Override the object database of this repository.
+    ///
+    /// The repository keeps its own reference to `odb`, so it can be dropped
+    /// after this call.
+    pub fn set_odb(&self, odb: &Odb) {
+        unsafe { raw::git_repository_set_odb(self.raw(), odb.raw()) }
+    }
+
+    /// Get the reference database for this repository.
+    pub fn refdb(&self) -> Result<Refdb, Error> {
+        let mut refdb = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_repository_refdb(&mut refdb, self.raw()));
+            Ok(Binding::from_raw(refdb))
+        }
+    }
+
+    /// Override the reference database of this repository.
+    ///
+    /// The repository keeps its own reference to `refdb`, so it can be
+    /// dropped after this call.
+    pub fn set_refdb(&self, refdb: &Refdb) {
+        unsafe { raw::git_repository_set_refdb(self.raw(), refdb.raw()) }
+    }
+
     /// Create a new branch pointing at a target commit
     ///
     /// A new direct reference will be created pointing to this target commit.
@@ -962,6 +1255,22 @@ impl Repository {
         }
     }
 
+    /// Get the full name of the upstream of a local branch.
+    ///
+    /// Given the full name of a local branch, such as `refs/heads/master`,
+    /// this returns the name of the remote-tracking branch it is configured
+    /// to track, such as `refs/remotes/origin/master`, without having to look
+    /// up either branch.
+    pub fn branch_upstream_name(&self, refname: &str) -> Result<Buf, Error> {
+        let refname = try!(CString::new(refname));
+        let buf = Buf::new();
+        unsafe {
+            try_call!(raw::git_branch_upstream_name(buf.raw(), self.raw,
+                                                    refname));
+        }
+        Ok(buf)
+    }
+
     /// Create new commit in the repository
     ///
     /// If the `update_ref` is not `None`, name of the reference that will be
@@ -998,6 +1307,88 @@ impl Repository {
         }
     }
 
+    /// Create new commit in the repository, recording a custom message in the
+    /// reflog of the updated reference.
+    ///
+    /// This behaves like `commit`, except that `commit` derives the reflog
+    /// message from the commit message, whereas this writes `reflog_message`
+    /// (for example "merge feature: Fast-forward"). When `update_ref` is a
+    /// symbolic reference such as "HEAD", its target is updated and the
+    /// message is recorded in both reflogs.
+    pub fn commit_with_reflog(&self,
+                              update_ref: Option<&str>,
+                              author: &Signature,
+                              committer: &Signature,
+                              message: &str,
+                              tree: &Tree,
+                              parents: &[&Commit],
+                              reflog_message: &str) -> Result<Oid, Error> {
+        let id = try!(self.commit(None, author, committer, message, tree,
+                                  parents));
+        let update_ref = match update_ref {
+            Some(name) => name,
+            None => return Ok(id),
+        };
+        let name = match self.find_reference(update_ref) {
+            Ok(ref r) => r.symbolic_target().unwrap_or(update_ref).to_string(),
+            Err(..) => update_ref.to_string(),
+        };
+        match parents.first() {
+            Some(parent) => {
+                try!(self.reference_matching(&name, id, true, parent.id(),
+                                             reflog_message));
+            }
+            None => {
+                try!(self.reference(&name, id, false, reflog_message));
+            }
+        }
+        Ok(id)
+    }
+
+    /// Create a sequence of commits, returning their ids in order.
+    ///
+    /// This is equivalent to calling `commit` for each spec without updating
+    /// any reference, except that each commit is written straight from the
+    /// ids of its tree and parents. Nothing is looked up, so the ids are not
+    /// checked to point at a tree and commits. A spec may use the commit
+    /// created by an earlier spec as a parent, which makes it possible to
+    /// write a whole chain of history in one call.
+    pub fn commit_batch(&self, specs: &[CommitSpec])
+                        -> Result<Vec<Oid>, Error> {
+        let mut ids = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let mut parents = Vec::with_capacity(spec.parents.len());
+            for parent in spec.parents.iter() {
+                let id = match *parent {
+                    CommitParent::Id(id) => id,
+                    CommitParent::Batch(i) => match ids.get(i) {
+                        Some(&id) => id,
+                        None => return Err(Error::from_str("commit parent \
+                                    must be created earlier in the batch")),
+                    },
+                };
+                parents.push(id);
+            }
+            let mut ptrs = parents.iter().map(|p| p.raw()).collect::<Vec<_>>();
+            let message = try!(CString::new(spec.message));
+            let mut raw = raw::git_oid { id: [0; raw::GIT_OID_RAWSZ] };
+            unsafe {
+                try_call!(raw::git_commit_create_from_ids(&mut raw,
+                                                          self.raw(),
+                                                          ptr::null(),
+                                                          spec.author.raw(),
+                                                          spec.committer.raw(),
+                                                          ptr::null(),
+                                                          message,
+                                                          spec.tree.raw(),
+                                                          ptrs.len() as size_t,
+                                                          ptrs.as_mut_ptr()));
+                ids.push(Binding::from_raw(&raw as *const _));
+            }
+        }
+        Ok(ids)
+    }
+
     /// Create a commit object from the given buffer and signature
     ///
     /// Given the unsigned commit object's contents, its signature and the
@@ -1275,6 +1666,27 @@ impl Repository {
         }
     }
 
+    /// Invoke `callback` for each submodule of this repository along with its
+    /// name, which is `None` if it is not valid utf-8, as with
+    /// `Submodule::name`.
+    ///
+    /// The submodule passed to the callback is only valid for the duration of
+    /// that call; use `find_submodule` to get a handle which outlives it.
+    /// Return `true` from the callback to continue iterating or `false` to
+    /// stop.
+    pub fn submodule_foreach<C>(&self, mut callback: C) -> Result<(), Error>
+        where C: FnMut(&Submodule, Option<&str>) -> bool
+    {
+        unsafe {
+            let mut data = SubmoduleForeachCbData { callback: &mut callback };
+            try_call!(raw::git_submodule_foreach(self.raw(),
+                                                 submodule_foreach_cb,
+                                                 &mut data as *mut _
+                                                           as *mut c_void));
+            Ok(())
+        }
+    }
+
     /// Lookup submodule information by name or path.
     ///
     /// Given either the submodule name or path (they are usually the same),
@@ -1303,6 +1715,58 @@ impl Repository {
         Ok(SubmoduleStatus::from_bits_truncate(ret as u32))
     }
 
+    /// Set the URL for the submodule in the configuration.
+    ///
+    /// After calling this, you may wish to call `Submodule::sync` to write
+    /// the changes to the checked out submodule repository.
+    pub fn submodule_set_url(&self, name: &str, url: &str)
+                             -> Result<(), Error> {
+        let name = try!(CString::new(name));
+        let url = try!(CString::new(url));
+        unsafe {
+            try_call!(raw::git_submodule_set_url(self.raw, name, url));
+        }
+        Ok(())
+    }
+
+    /// Set the branch for the submodule in the configuration.
+    ///
+    /// After calling this, you may wish to call `Submodule::sync` to write
+    /// the changes to the checked out submodule repository.
+    pub fn submodule_set_branch(&self, name: &str, branch: &str)
+                                -> Result<(), Error> {
+        let name = try!(CString::new(name));
+        let branch = try!(CString::new(branch));
+        unsafe {
+            try_call!(raw::git_submodule_set_branch(self.raw, name, branch));
+        }
+        Ok(())
+    }
+
+    /// Set the ignore rule for the submodule in the configuration.
+    ///
+    /// This does not affect any currently-loaded instances.
+    pub fn submodule_set_ignore(&self, name: &str, ignore: SubmoduleIgnore)
+                                -> Result<(), Error> {
+        let name = try!(CString::new(name));
+        unsafe {
+            try_call!(raw::git_submodule_set_ignore(self.raw, name, ignore));
+        }
+        Ok(())
+    }
+
+    /// Set the update rule for the submodule in the configuration.
+    ///
+    /// This setting won't affect any existing instances.
+    pub fn submodule_set_update(&self, name: &str, update: SubmoduleUpdate)
+                                -> Result<(), Error> {
+        let name = try!(CString::new(name));
+        unsafe {
+            try_call!(raw::git_submodule_set_update(self.raw, name, update));
+        }
+        Ok(())
+    }
+
     /// Lookup a reference to one of the objects in a repository.
     pub fn find_tree(&self, oid: Oid) -> Result<Tree, Error> {
         let mut raw = ptr::null_mut();
@@ -1433,7 +1897,9 @@ impl Repository {
 
     /// Updates files in the working tree to match the content of the index.
     ///
-    /// If the index is `None`, the repository's index will be used.
+    /// If the index is `None`, the repository's index will be used. Any other
+    /// index, such as the in-memory result of `merge_commits`, may also be
+    /// given to write its contents out to the working directory.
     pub fn checkout_index(&self,
                           index: Option<&mut Index>,
                           opts: Option<&mut CheckoutBuilder>) -> Result<(), Error> {
@@ -1538,6 +2004,44 @@ impl Repository {
         }
     }
 
+    /// Merge the contents of the three sides of a file given as index
+    /// entries, such as the stages of a conflicted index entry.
+    ///
+    /// The blobs referenced by the entries are looked up in this
+    /// repository. Any side may be `None` if the file does not exist on that
+    /// side, for example when it was added on both branches.
+    pub fn merge_file_from_index(&self,
+                                 ancestor: Option<&IndexEntry>,
+                                 ours: Option<&IndexEntry>,
+                                 theirs: Option<&IndexEntry>,
+                                 opts: Option<&MergeFileOptions>)
+                                 -> Result<MergeFileResult, Error> {
+        let ancestor_path = try!(::opt_cstr(ancestor.map(|e| &e.path[..])));
+        let ours_path = try!(::opt_cstr(ours.map(|e| &e.path[..])));
+        let theirs_path = try!(::opt_cstr(theirs.map(|e| &e.path[..])));
+        let raw_entry = |e: Option<&IndexEntry>, path: &Option<CString>| {
+            match (e, path.as_ref()) {
+                (Some(e), Some(path)) => Some(::index::raw_entry(e, path)),
+                _ => None,
+            }
+        };
+        let ancestor = raw_entry(ancestor, &ancestor_path);
+        let ours = raw_entry(ours, &ours_path);
+        let theirs = raw_entry(theirs, &theirs_path);
+        let as_ptr = |e: &Option<raw::git_index_entry>| {
+            e.as_ref().map(|e| e as *const _).unwrap_or(ptr::null())
+        };
+        unsafe {
+            let mut ret = mem::zeroed();
+            try_call!(raw::git_merge_file_from_index(&mut ret, self.raw(),
+                                                     as_ptr(&ancestor),
+                                                     as_ptr(&ours),
+                                                     as_ptr(&theirs),
+                                                     opts.map(|o| o.raw())));
+            Ok(::merge::file_result_from_raw(ret))
+        }
+    }
+
     /// Remove all the metadata associated with an ongoing command like merge,
     /// revert, cherry-pick, etc. For example: MERGE_HEAD, MERGE_MSG, etc.
     pub fn cleanup_state(&self) -> Result<(), Error> {
@@ -1568,6 +2072,126 @@ impl Repository {
         }
     }
 
+    /// Cherry-pick the given commit, producing changes in the index and
+    /// working directory.
+    pub fn cherrypick(&self,
+                      commit: &Commit,
+                      options: Option<&mut CherrypickOptions>)
+                      -> Result<(), Error> {
+        let raw_opts = options.map(|o| o.raw());
+        let ptr_raw_opts = match raw_opts.as_ref() {
+            Some(v) => v,
+            None => ptr::null(),
+        };
+        unsafe {
+            try_call!(raw::git_cherrypick(self.raw(), commit.raw(),
+                                          ptr_raw_opts));
+        }
+        Ok(())
+    }
+
+    /// Cherry-pick the given commit against the given "our" commit, producing
+    /// an index that reflects the result of the cherry-pick.
+    ///
+    /// For merge commits `mainline` selects the 1-based parent to diff
+    /// against, and should otherwise be 0. The repository and working
+    /// directory are left untouched.
+    pub fn cherrypick_commit(&self,
+                             cherrypick_commit: &Commit,
+                             our_commit: &Commit,
+                             mainline: u32,
+                             options: Option<&MergeOptions>)
+                             -> Result<Index, Error> {
+        let mut ret = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_cherrypick_commit(&mut ret, self.raw(),
+                                                 cherrypick_commit.raw(),
+                                                 our_commit.raw(),
+                                                 mainline,
+                                                 options.map(|o| o.raw())));
+            Ok(Binding::from_raw(ret))
+        }
+    }
+
+    /// Reverts the given commit, producing changes in the index and working
+    /// directory.
+    pub fn revert(&self,
+                  commit: &Commit,
+                  options: Option<&mut RevertOptions>)
+                  -> Result<(), Error> {
+        let raw_opts = options.map(|o| o.raw());
+        let ptr_raw_opts = match raw_opts.as_ref() {
+            Some(v) => v,
+            None => ptr::null(),
+        };
+        unsafe {
+            try_call!(raw::git_revert(self.raw(), commit.raw(),
+                                      ptr_raw_opts));
+        }
+        Ok(())
+    }
+
+    /// Reverts the given commit against the given "our" commit, producing an
+    /// index that reflects the result of the revert.
+    ///
+    /// For merge commits `mainline` selects the 1-based parent to diff
+    /// against, and should otherwise be 0. The repository and working
+    /// directory are left untouched.
+    pub fn revert_commit(&self,
+                         revert_commit: &Commit,
+                         our_commit: &Commit,
+                         mainline: u32,
+                         options: Option<&MergeOptions>)
+                         -> Result<Index, Error> {
+        let mut ret = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_revert_commit(&mut ret, self.raw(),
+                                             revert_commit.raw(),
+                                             our_commit.raw(),
+                                             mainline,
+                                             options.map(|o| o.raw())));
+            Ok(Binding::from_raw(ret))
+        }
+    }
+
+    /// Initializes a rebase operation to rebase the changes in `branch`
+    /// relative to `upstream` onto another branch. To begin the rebase
+    /// process, iterate over the returned `Rebase` to apply each operation.
+    ///
+    /// `branch` defaults to the current branch, `upstream` to the branch's
+    /// configured upstream, and `onto` to `upstream`.
+    pub fn rebase(&self,
+                  branch: Option<&AnnotatedCommit>,
+                  upstream: Option<&AnnotatedCommit>,
+                  onto: Option<&AnnotatedCommit>,
+                  opts: Option<&mut RebaseOptions>)
+                  -> Result<Rebase, Error> {
+        let mut rebase = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_rebase_init(&mut rebase,
+                                           self.raw(),
+                                           branch.map(|c| c.raw()),
+                                           upstream.map(|c| c.raw()),
+                                           onto.map(|c| c.raw()),
+                                           opts.map(|o| o.raw())
+                                               .unwrap_or(ptr::null())));
+            Ok(Rebase::from_raw(rebase))
+        }
+    }
+
+    /// Opens an existing rebase that was previously started by either an
+    /// invocation of `rebase` or by another client.
+    pub fn open_rebase(&self, opts: Option<&mut RebaseOptions>)
+                       -> Result<Rebase, Error> {
+        let mut rebase = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_rebase_open(&mut rebase, self.raw(),
+                                           opts.map(|o| o.raw())
+                                               .unwrap_or(ptr::null())));
+            Ok(Rebase::from_raw(rebase))
+        }
+    }
+
     /// Add a note for an object
     ///
     /// The `notes_ref` argument is the canonical name of the reference to use,
@@ -1723,6 +2347,22 @@ impl Repository {
         }
     }
 
+    /// Count the number of unique commits between a local branch and its
+    /// configured upstream.
+    ///
+    /// `local_branch` is the short name of a local branch, such as "master".
+    /// This returns `(ahead, behind)` as `graph_ahead_behind` does, or an
+    /// error with the code `ErrorCode::NotFound` if the branch has no
+    /// upstream.
+    pub fn branch_ahead_behind(&self, local_branch: &str)
+                               -> Result<(usize, usize), Error> {
+        let branch = try!(self.find_branch(local_branch, BranchType::Local));
+        let local = try!(branch.get().peel_to_commit()).id();
+        let upstream = try!(branch.upstream());
+        let upstream = try!(upstream.get().peel_to_commit()).id();
+        self.graph_ahead_behind(local, upstream)
+    }
+
     /// Determine if a commit is the descendant of another commit
     pub fn graph_descendant_of(&self, commit: Oid, ancestor: Oid)
                                -> Result<bool, Error> {
@@ -1734,6 +2374,25 @@ impl Repository {
         }
     }
 
+    /// Determine if a commit is reachable from any of a list of commits by
+    /// following parent edges.
+    ///
+    /// A commit is considered reachable from itself, so this returns `true`
+    /// if `commit` is one of `descendants`. This is useful to check whether
+    /// a commit has already been merged into any of a set of branch tips.
+    pub fn graph_reachable_from_any(&self, commit: Oid, descendants: &[Oid])
+                                    -> Result<bool, Error> {
+        // The bundled libgit2 has no `git_graph_reachable_from_any`, so
+        // check each descendant in turn.
+        for &descendant in descendants {
+            if descendant == commit ||
+               try!(self.graph_descendant_of(descendant, commit)) {
+                return Ok(true)
+            }
+        }
+        Ok(false)
+    }
+
     /// Read the reflog for the given reference
     ///
     /// If there is no reflog file for the given reference yet, an empty reflog
@@ -1777,6 +2436,11 @@ impl Repository {
     }
 
     /// Ensure that the given reference has a reflog.
+    ///
+    /// By default reflogs are only kept for `HEAD` and for references under
+    /// `refs/heads`, `refs/remotes` and `refs/notes`. This creates an empty
+    /// reflog for any other reference so that subsequent updates to it are
+    /// logged as well.
     pub fn reference_ensure_log(&self, name: &str) -> Result<(), Error> {
         let name = try!(CString::new(name));
         unsafe {
@@ -1977,6 +2641,11 @@ impl Repository {
     }
 
     /// Apply a single stashed state from the stash list.
+    ///
+    /// If the stashed changes conflict with the current state, an error with
+    /// the code `ErrorCode::Conflict` is returned. Depending on where the
+    /// conflict is found, the conflicts may have been written to the index
+    /// and working directory for the user to resolve.
     pub fn stash_apply(&mut self,
                        index: usize,
                        opts: Option<&mut StashApplyOptions>)
@@ -1984,8 +2653,17 @@ impl Repository {
         unsafe {
             let opts = opts.map(|opts| opts.raw());
             try_call!(raw::git_stash_apply(self.raw(), index, opts));
-            Ok(())
         }
+        // libgit2 reports success when the stash merged with conflicts, in
+        // which case the conflicts are left in the index.
+        if try!(self.index()).has_conflicts() {
+            let msg = try!(CString::new("stash applied with conflicts"));
+            unsafe {
+                raw::giterr_set_str(raw::GITERR_STASH as c_int, msg.as_ptr());
+            }
+            return Err(Error::last_error(raw::GIT_ECONFLICT).unwrap())
+        }
+        Ok(())
     }
 
     /// Loop over all the stashed states and issue a callback for each one.
@@ -2012,15 +2690,15 @@ impl Repository {
     }
 
     /// Apply a single stashed state from the stash list and remove it from the list if successful.
+    ///
+    /// As with `stash_apply`, conflicts are reported as an error with the
+    /// code `ErrorCode::Conflict`, and the stashed state is kept.
     pub fn stash_pop(&mut self,
                      index: usize,
                      opts: Option<&mut StashApplyOptions>)
                      -> Result<(), Error> {
-        unsafe {
-            let opts = opts.map(|opts| opts.raw());
-            try_call!(raw::git_stash_pop(self.raw(), index, opts));
-            Ok(())
-        }
+        try!(self.stash_apply(index, opts));
+        self.stash_drop(index)
     }
 
     /// Add ignore rules for a repository.
@@ -2167,6 +2845,9 @@ impl RepositoryInitOptions {
 
     /// If set, this will be used to initialize the "description" file in the
     /// repository instead of using the template content.
+    ///
+    /// This only takes effect when no external template directory is copied,
+    /// as otherwise the template's own description file is used.
     pub fn description(&mut self, desc: &str) -> &mut RepositoryInitOptions {
         self.description = Some(CString::new(desc).unwrap());
         self
@@ -2219,13 +2900,122 @@ impl RepositoryInitOptions {
     }
 }
 
+impl<'a> CommitSpec<'a> {
+    /// Creates a spec for a commit of the tree `tree` with no parents.
+    pub fn new(author: &'a Signature<'a>,
+               committer: &'a Signature<'a>,
+               message: &'a str,
+               tree: Oid) -> CommitSpec<'a> {
+        CommitSpec {
+            author: author,
+            committer: committer,
+            message: message,
+            tree: tree,
+            parents: Vec::new(),
+        }
+    }
+
+    /// Add an existing commit as the next parent of this commit.
+    pub fn parent(&mut self, id: Oid) -> &mut CommitSpec<'a> {
+        self.parents.push(CommitParent::Id(id));
+        self
+    }
+
+    /// Add the commit created from the `index`th spec of the same batch as
+    /// the next parent of this commit.
+    ///
+    /// The index must refer to a spec which comes before this one.
+    pub fn batch_parent(&mut self, index: usize) -> &mut CommitSpec<'a> {
+        self.parents.push(CommitParent::Batch(index));
+        self
+    }
+}
+
+struct MergeheadForeachCbData<'a> {
+    callback: &'a mut FnMut(&Oid) -> bool,
+}
+
+extern fn mergehead_foreach_cb(oid: *const raw::git_oid,
+                               payload: *mut c_void) -> c_int {
+    panic::wrap(|| unsafe {
+        let data = &mut *(payload as *mut MergeheadForeachCbData);
+        let res = (data.callback)(&Binding::from_raw(oid));
+        if res { 0 } else { 1 }
+    }).unwrap_or(1)
+}
+
+struct FetchheadForeachCbData<'a> {
+    callback: &'a mut FnMut(Option<&[u8]>, Option<&[u8]>, &Oid, bool) -> bool,
+}
+
+extern fn fetchhead_foreach_cb(ref_name: *const c_char,
+                               remote_url: *const c_char,
+                               oid: *const raw::git_oid,
+                               is_merge: c_uint,
+                               payload: *mut c_void) -> c_int {
+    panic::wrap(|| unsafe {
+        let data = &mut *(payload as *mut FetchheadForeachCbData);
+        let ref_name = ::opt_bytes(&payload, ref_name);
+        let remote_url = ::opt_bytes(&payload, remote_url);
+        let oid = Binding::from_raw(oid);
+        let res = (data.callback)(ref_name, remote_url, &oid, is_merge == 1);
+        if res { 0 } else { 1 }
+    }).unwrap_or(1)
+}
+
+struct SubmoduleForeachCbData<'a> {
+    callback: &'a mut FnMut(&Submodule, Option<&str>) -> bool,
+}
+
+extern fn submodule_foreach_cb(submodule: *mut raw::git_submodule,
+                               name: *const c_char,
+                               payload: *mut c_void) -> c_int {
+    panic::wrap(|| unsafe {
+        let data = &mut *(payload as *mut SubmoduleForeachCbData);
+        // The submodule is owned by libgit2 and must not be freed here.
+        let submodule = mem::ManuallyDrop::new(Submodule::from_raw(submodule));
+        let name = ::opt_bytes(&payload, name)
+                       .and_then(|s| str::from_utf8(s).ok());
+        let res = (data.callback)(&submodule, name);
+        if res { 0 } else { 1 }
+    }).unwrap_or(1)
+}
+
+struct AttrForeachCbData<'a> {
+    callback: &'a mut FnMut(&str, &[u8]) -> bool,
+}
+
+extern fn attr_foreach_cb(name: *const c_char,
+                          value: *const c_char,
+                          payload: *mut c_void) -> c_int {
+    panic::wrap(|| unsafe {
+        let data = &mut *(payload as *mut AttrForeachCbData);
+        let name = match str::from_utf8(CStr::from_ptr(name).to_bytes()) {
+            Ok(name) => name,
+            Err(..) => return 0,
+        };
+        let value = if value.is_null() {
+            &[][..]
+        } else {
+            CStr::from_ptr(value).to_bytes()
+        };
+        let res = (data.callback)(name, value);
+        if res { 0 } else { 1 }
+    }).unwrap_or(1)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::env;
     use std::ffi::OsStr;
     use std::fs;
+    use std::io::prelude::*;
     use std::path::Path;
     use tempdir::TempDir;
-    use {Repository, Oid, ObjectType, ResetType};
+    use {Repository, RepositoryState, Oid, ObjectType, ResetType, Odb};
+    use {AttrCheckFlags, AttrCheckOrder, BranchType, CommitSpec, ErrorCode};
+    use RevparseMode;
+    use {Signature, Time};
     use build::CheckoutBuilder;
 
     #[test]
@@ -2247,6 +3037,73 @@ mod tests {
         assert!(repo.namespace().is_none());
     }
 
+    #[test]
+    fn namespace() {
+        let (td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap().target().unwrap();
+
+        let repo = Repository::open(td.path()).unwrap();
+        repo.set_namespace("foo").unwrap();
+        assert_eq!(repo.namespace(), Some("foo"));
+        repo.reference("refs/heads/bar", head, false, "create").unwrap();
+        repo.remove_namespace().unwrap();
+        assert!(repo.namespace().is_none());
+
+        let repo = Repository::open(td.path()).unwrap();
+        let r = repo.find_reference("refs/namespaces/foo/refs/heads/bar")
+                    .unwrap();
+        assert_eq!(r.target(), Some(head));
+        assert!(repo.find_reference("refs/heads/bar").is_err());
+    }
+
+    #[test]
+    fn init_opts_initial_head() {
+        let td = TempDir::new("test").unwrap();
+        let path = td.path().join("a/b");
+        let mut opts = ::RepositoryInitOptions::new();
+        opts.mkpath(true)
+            .initial_head("main")
+            .origin_url("https://example.com/repo.git");
+        let repo = Repository::init_opts(&path, &opts).unwrap();
+
+        let head = repo.find_reference("HEAD").unwrap();
+        assert_eq!(head.symbolic_target(), Some("refs/heads/main"));
+        assert_eq!(repo.head().err().unwrap().code(),
+                   ::ErrorCode::UnbornBranch);
+        let origin = repo.find_remote("origin").unwrap();
+        assert_eq!(origin.url(), Some("https://example.com/repo.git"));
+
+        let mut opts = ::RepositoryInitOptions::new();
+        opts.no_reinit(true);
+        assert!(Repository::init_opts(&path, &opts).is_err());
+    }
+
+    #[test]
+    fn init_opts_template() {
+        let td = TempDir::new("test").unwrap();
+        let template = td.path().join("template");
+        fs::create_dir_all(template.join("hooks")).unwrap();
+        fs::File::create(template.join("hooks/pre-commit")).unwrap()
+            .write_all(b"#!/bin/sh\nexit 0\n").unwrap();
+
+        let mut opts = ::RepositoryInitOptions::new();
+        opts.external_template(true).template_path(&template);
+        let repo = Repository::init_opts(td.path().join("a"), &opts).unwrap();
+        let mut hook = String::new();
+        fs::File::open(repo.path().join("hooks/pre-commit")).unwrap()
+            .read_to_string(&mut hook).unwrap();
+        assert_eq!(hook, "#!/bin/sh\nexit 0\n");
+
+        // The description is only written when no external template is used.
+        let mut opts = ::RepositoryInitOptions::new();
+        opts.external_template(false).description("my repository");
+        let repo = Repository::init_opts(td.path().join("b"), &opts).unwrap();
+        let mut description = String::new();
+        fs::File::open(repo.path().join("description")).unwrap()
+            .read_to_string(&mut description).unwrap();
+        assert_eq!(description, "my repository");
+    }
+
     #[test]
     fn smoke_open() {
         let td = TempDir::new("test").unwrap();
@@ -2261,6 +3118,25 @@ mod tests {
         assert_eq!(repo.state(), ::RepositoryState::Clean);
     }
 
+    #[test]
+    fn open_from_env() {
+        let td = TempDir::new("test").unwrap();
+        Repository::init_bare(td.path()).unwrap();
+
+        let prev = env::var_os("GIT_DIR");
+        env::set_var("GIT_DIR", td.path());
+        let repo = Repository::open_from_env();
+        match prev {
+            Some(prev) => env::set_var("GIT_DIR", prev),
+            None => env::remove_var("GIT_DIR"),
+        }
+
+        let repo = repo.unwrap();
+        assert!(repo.is_bare());
+        assert_eq!(::test::realpath(&repo.path()).unwrap(),
+                   ::test::realpath(&td.path()).unwrap());
+    }
+
     #[test]
     fn smoke_open_bare() {
         let td = TempDir::new("test").unwrap();
@@ -2296,6 +3172,233 @@ mod tests {
         t!(repo.reset(&obj, ResetType::Soft, Some(&mut opts)));
     }
 
+    #[test]
+    fn branch_upstream_name() {
+        let (_td, repo) = ::test::repo_init();
+        let commit = t!(t!(repo.head()).peel_to_commit());
+        t!(repo.remote("origin", "https://example.com/repo"));
+        t!(repo.reference("refs/remotes/origin/main", commit.id(), false,
+                          "remote"));
+        let mut branch = t!(repo.branch("foo", &commit, false));
+        assert!(repo.branch_upstream_name("refs/heads/foo").is_err());
+
+        t!(branch.set_upstream(Some("origin/main")));
+        let name = t!(repo.branch_upstream_name("refs/heads/foo"));
+        assert_eq!(t!(name.as_str()), "refs/remotes/origin/main");
+    }
+
+    #[test]
+    fn attr_foreach() {
+        let (td, repo) = ::test::repo_init();
+        t!(t!(fs::File::create(td.path().join(".gitattributes")))
+               .write_all(b"*.txt text eol=crlf -diff\n*.rs !text\n"));
+
+        let mut attrs = Vec::new();
+        let order = AttrCheckOrder::FileThenIndex;
+        let flags = AttrCheckFlags::empty();
+        t!(repo.attr_foreach(order, flags, Path::new("a.txt"), |name, value| {
+            attrs.push((name.to_string(), value.to_vec()));
+            true
+        }));
+        attrs.sort();
+        assert_eq!(attrs.len(), 3);
+        assert_eq!(attrs[0].0, "diff");
+        assert_eq!(attrs[1], ("eol".to_string(), b"crlf".to_vec()));
+        assert_eq!(attrs[2].0, "text");
+
+        let mut names = Vec::new();
+        t!(repo.attr_foreach(order, AttrCheckFlags::NO_SYSTEM,
+                             Path::new("a.rs"), |name, value| {
+            names.push(name.to_string());
+            assert!(value.is_empty());
+            true
+        }));
+        assert_eq!(names, ["text"]);
+
+        let mut count = 0;
+        t!(repo.attr_foreach(order, flags, Path::new("a.txt"), |_, _| {
+            count += 1;
+            false
+        }));
+        assert_eq!(count, 1);
+
+        // The .gitattributes file is not in the index.
+        let mut count = 0;
+        t!(repo.attr_foreach(AttrCheckOrder::IndexOnly,
+                             AttrCheckFlags::NO_SYSTEM,
+                             Path::new("a.txt"), |_, _| {
+            count += 1;
+            true
+        }));
+        assert_eq!(count, 0);
+
+        t!(t!(fs::File::create(td.path().join(".gitattributes")))
+               .write_all(b"*.bin \xff good\n"));
+        let mut names = Vec::new();
+        t!(repo.attr_foreach(order, flags, Path::new("a.bin"), |name, _| {
+            names.push(name.to_string());
+            true
+        }));
+        assert_eq!(names, ["good"]);
+    }
+
+    #[test]
+    fn commit_with_reflog() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let parent = t!(t!(repo.head()).peel_to_commit());
+        let tree = t!(parent.tree());
+
+        let id = t!(repo.commit_with_reflog(Some("HEAD"), &sig, &sig,
+                                            "commit message", &tree,
+                                            &[&parent],
+                                            "merge feature: Fast-forward"));
+        assert_eq!(t!(repo.head()).target(), Some(id));
+        for name in &["HEAD", "refs/heads/master"] {
+            let reflog = t!(repo.reflog(name));
+            let entry = reflog.get(0).unwrap();
+            assert_eq!(entry.message(), Some("merge feature: Fast-forward"));
+            assert_eq!(entry.id_new(), id);
+            assert_eq!(entry.id_old(), parent.id());
+        }
+
+        // The first parent must still be the tip of the updated branch.
+        assert!(repo.commit_with_reflog(Some("HEAD"), &sig, &sig, "stale",
+                                        &tree, &[&parent], "stale").is_err());
+    }
+
+    #[test]
+    fn merge_message() {
+        let (_td, repo) = ::test::repo_init();
+        assert!(!repo.has_message());
+        assert_eq!(repo.message().unwrap_err().code(), ErrorCode::NotFound);
+        t!(repo.remove_message());
+
+        t!(t!(fs::File::create(repo.path().join("MERGE_MSG")))
+               .write_all(b"Merge branch 'feature'\n"));
+        assert!(repo.has_message());
+        assert_eq!(t!(repo.message()), "Merge branch 'feature'\n");
+
+        t!(repo.remove_message());
+        assert!(!repo.has_message());
+        t!(repo.remove_message());
+    }
+
+    #[test]
+    fn set_head_reflog() {
+        let (_td, repo) = ::test::repo_init();
+        let head = t!(t!(repo.head()).peel_to_commit());
+        t!(repo.branch("foo", &head, false));
+
+        t!(repo.set_head_bytes(b"refs/heads/foo"));
+        assert_eq!(t!(repo.head()).name(), Some("refs/heads/foo"));
+        let reflog = t!(repo.reflog("HEAD"));
+        assert_eq!(reflog.get(0).unwrap().message(),
+                   Some("checkout: moving from master to foo"));
+
+        t!(repo.reference_symbolic("HEAD", "refs/heads/master", true,
+                                   "custom: back to master"));
+        let reflog = t!(repo.reflog("HEAD"));
+        assert_eq!(reflog.get(0).unwrap().message(),
+                   Some("custom: back to master"));
+    }
+
+    #[test]
+    fn revparse_range() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let tree = t!(t!(repo.head()).peel_to_tree());
+        let mut ids = vec![t!(t!(repo.head()).peel_to_commit()).id()];
+        for _ in 0..2 {
+            let parent = t!(repo.find_commit(*ids.last().unwrap()));
+            ids.push(t!(repo.commit(Some("HEAD"), &sig, &sig, "next", &tree,
+                                    &[&parent])));
+        }
+
+        let rev = t!(repo.revparse("HEAD~2..HEAD"));
+        assert_eq!(rev.from().unwrap().id(), ids[0]);
+        assert_eq!(rev.to().unwrap().id(), ids[2]);
+        assert_eq!(rev.mode(), RevparseMode::RANGE);
+        assert!(rev.mode().is_range());
+        assert!(!rev.mode().is_merge_base());
+
+        let rev = t!(repo.revparse("HEAD~1...HEAD"));
+        assert_eq!(rev.from().unwrap().id(), ids[1]);
+        assert_eq!(rev.to().unwrap().id(), ids[2]);
+        assert!(rev.mode().is_range());
+        assert!(rev.mode().is_merge_base());
+
+        let rev = t!(repo.revparse("HEAD~1"));
+        assert_eq!(rev.mode(), RevparseMode::SINGLE);
+        assert!(rev.to().is_none());
+    }
+
+    #[test]
+    fn reset_orig_head() {
+        let (_td, repo) = ::test::repo_init();
+        assert_eq!(repo.orig_head().unwrap_err().code(), ErrorCode::NotFound);
+
+        let sig = t!(repo.signature());
+        let first = t!(t!(repo.head()).peel_to_commit());
+        let tree = t!(first.tree());
+        let second = t!(repo.commit(Some("HEAD"), &sig, &sig, "second", &tree,
+                                    &[&first]));
+
+        t!(repo.reset(first.as_object(), ResetType::Hard, None));
+        assert_eq!(t!(repo.head()).target(), Some(first.id()));
+        assert_eq!(t!(repo.orig_head()), second);
+    }
+
+    #[test]
+    fn reset_failure_keeps_orig_head() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let first = t!(t!(repo.head()).peel_to_commit());
+        let tree = t!(first.tree());
+        let second = t!(repo.commit(Some("HEAD"), &sig, &sig, "second", &tree,
+                                    &[&first]));
+        t!(repo.reset(first.as_object(), ResetType::Hard, None));
+        assert_eq!(t!(repo.orig_head()), second);
+
+        // A blob cannot be peeled to a commit, so this reset fails.
+        let blob = t!(repo.find_object(t!(repo.blob(b"data")), None));
+        assert!(repo.reset(&blob, ResetType::Hard, None).is_err());
+        assert_eq!(t!(repo.head()).target(), Some(first.id()));
+        assert_eq!(t!(repo.orig_head()), second);
+    }
+
+    #[test]
+    fn reset_hard_progress() {
+        let (td, repo) = ::test::repo_init();
+        let path = td.path().join("foo");
+        t!(t!(fs::File::create(&path)).write_all(b"foo"));
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("foo")));
+        t!(index.write());
+        let tree = t!(repo.find_tree(t!(index.write_tree())));
+        let sig = t!(repo.signature());
+        let head = t!(t!(repo.head()).peel_to_commit());
+        let id = t!(repo.commit(Some("HEAD"), &sig, &sig, "foo", &tree,
+                                &[&head]));
+        let commit = t!(repo.find_object(id, None));
+
+        t!(t!(fs::File::create(&path)).write_all(b"modified"));
+        let mut paths = Vec::new();
+        {
+            let mut opts = CheckoutBuilder::new();
+            opts.progress(|path, _cur, _total| {
+                if let Some(path) = path {
+                    paths.push(path.to_path_buf());
+                }
+            });
+            t!(repo.reset(&commit, ResetType::Hard, Some(&mut opts)));
+        }
+        assert_eq!(paths, [Path::new("foo")]);
+        let mut contents = String::new();
+        t!(t!(fs::File::open(&path)).read_to_string(&mut contents));
+        assert_eq!(contents, "foo");
+    }
+
     #[test]
     fn makes_dirs() {
         let td = TempDir::new("foo").unwrap();
@@ -2338,6 +3441,104 @@ mod tests {
                                      &[&subdir]).is_ok());
     }
 
+    #[test]
+    fn open_ext_nested() {
+        let td = TempDir::new("test").unwrap();
+        let inner = td.path().join("inner");
+        let inner_sub = inner.join("sub");
+        fs::create_dir_all(&inner_sub).unwrap();
+        Repository::init(td.path()).unwrap();
+        Repository::init(&inner).unwrap();
+        let none = &[] as &[&OsStr];
+
+        let repo = Repository::open_ext(&inner, ::RepositoryOpenFlags::NO_SEARCH,
+                                        none).unwrap();
+        assert_eq!(::test::realpath(&repo.path()).unwrap(),
+                   ::test::realpath(&inner.join(".git")).unwrap());
+
+        assert!(Repository::open_ext(&inner_sub,
+                                     ::RepositoryOpenFlags::NO_SEARCH,
+                                     none).is_err());
+        let repo = Repository::open_ext(&inner_sub,
+                                        ::RepositoryOpenFlags::empty(),
+                                        none).unwrap();
+        assert_eq!(::test::realpath(&repo.path()).unwrap(),
+                   ::test::realpath(&inner.join(".git")).unwrap());
+    }
+
+    #[test]
+    fn discover_ceiling() {
+        let td = TempDir::new("test").unwrap();
+        let project = td.path().join("project");
+        let sub = project.join("sub");
+        fs::create_dir_all(&sub).unwrap();
+        Repository::init(td.path()).unwrap();
+
+        let repo = Repository::discover_ceiling(&sub, &[] as &[&OsStr], false)
+                              .unwrap();
+        assert_eq!(::test::realpath(&repo.path()).unwrap(),
+                   ::test::realpath(&td.path().join(".git")).unwrap());
+
+        let err = Repository::discover_ceiling(&sub, &[&project], false)
+                             .err().unwrap();
+        assert_eq!(err.code(), ::ErrorCode::NotFound);
+    }
+
+    #[test]
+    fn commondir_worktree() {
+        let (_td, repo) = ::test::repo_init();
+        assert_eq!(::test::realpath(repo.commondir().unwrap()).unwrap(),
+                   ::test::realpath(repo.path()).unwrap());
+
+        let (wt, admin) = ::test::worktree_init(&repo, "wt");
+
+        let worktree = Repository::open(&wt).unwrap();
+        assert!(worktree.is_worktree());
+        assert_eq!(::test::realpath(worktree.path()).unwrap(),
+                   ::test::realpath(&admin).unwrap());
+        assert_eq!(::test::realpath(worktree.commondir().unwrap()).unwrap(),
+                   ::test::realpath(repo.path()).unwrap());
+    }
+
+    #[test]
+    fn reference_matching() {
+        let (_td, repo) = graph_repo_init();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let second = head.id();
+        let first = head.parent_id(0).unwrap();
+
+        repo.reference("refs/heads/cas", first, false, "create").unwrap();
+        let r = repo.reference_matching("refs/heads/cas", second, true, first,
+                                        "compare and swap").unwrap();
+        assert_eq!(r.target(), Some(second));
+
+        // The ref no longer points at `first`, so this must not update it.
+        let err = repo.reference_matching("refs/heads/cas", first, true, first,
+                                          "stale").err().unwrap();
+        assert_eq!(err.code(), ::ErrorCode::Modified);
+        let r = repo.find_reference("refs/heads/cas").unwrap();
+        assert_eq!(r.target(), Some(second));
+    }
+
+    #[test]
+    fn reference_symbolic_matching() {
+        let (_td, repo) = ::test::repo_init();
+        let target = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/heads/other", target, false, "create").unwrap();
+
+        let head = repo.reference_symbolic_matching("HEAD", "refs/heads/other",
+                                                    true, "refs/heads/master",
+                                                    "switch").unwrap();
+        assert_eq!(head.symbolic_target(), Some("refs/heads/other"));
+
+        let err = repo.reference_symbolic_matching("HEAD", "refs/heads/master",
+                                                   true, "refs/heads/master",
+                                                   "stale").err().unwrap();
+        assert_eq!(err.code(), ::ErrorCode::Modified);
+        let head = repo.find_reference("HEAD").unwrap();
+        assert_eq!(head.symbolic_target(), Some("refs/heads/other"));
+    }
+
     fn graph_repo_init() -> (TempDir, Repository) {
         let (_td, repo) = ::test::repo_init();
         {
@@ -2383,6 +3584,146 @@ mod tests {
         assert!(!repo.graph_descendant_of(head_parent_id, head_id).unwrap());
     }
 
+    #[test]
+    fn graph_reachable_from_any() {
+        let (_td, repo, merge) = merge_commit_init();
+        let merge = repo.find_commit(merge).unwrap();
+        let main = merge.parent_id(0).unwrap();
+        let side = merge.parent_id(1).unwrap();
+        let base = repo.find_commit(main).unwrap().parent_id(0).unwrap();
+
+        assert!(repo.graph_reachable_from_any(base, &[main, side]).unwrap());
+        assert!(repo.graph_reachable_from_any(side, &[main, side]).unwrap());
+        assert!(!repo.graph_reachable_from_any(side, &[main]).unwrap());
+        assert!(repo.graph_reachable_from_any(side, &[main, merge.id()])
+                    .unwrap());
+        assert!(!repo.graph_reachable_from_any(main, &[]).unwrap());
+    }
+
+    #[test]
+    fn mergehead_foreach() {
+        let (_td, repo, merge) = merge_commit_init();
+        let merge = repo.find_commit(merge).unwrap();
+        let heads = merge.parent_ids().collect::<Vec<_>>();
+        let mut file = fs::File::create(repo.path().join("MERGE_HEAD"))
+                                .unwrap();
+        for id in &heads {
+            writeln!(file, "{}", id).unwrap();
+        }
+        drop(file);
+
+        let mut seen = Vec::new();
+        repo.mergehead_foreach(|id| { seen.push(*id); true }).unwrap();
+        assert_eq!(seen, heads);
+
+        let mut count = 0;
+        repo.mergehead_foreach(|_| { count += 1; false }).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn fetchhead_foreach() {
+        let (td, upstream) = ::test::repo_init();
+        let head = upstream.head().unwrap().target().unwrap();
+        let url = ::test::path2url(td.path());
+
+        let td2 = TempDir::new("test").unwrap();
+        let repo = Repository::init(td2.path()).unwrap();
+        let mut origin = repo.remote("origin", &url).unwrap();
+        origin.fetch(&["master"], None, None).unwrap();
+
+        let mut seen = Vec::new();
+        repo.fetchhead_foreach(|name, remote_url, id, is_merge| {
+            seen.push((name.map(|s| s.to_vec()), remote_url.map(|s| s.to_vec()),
+                       *id, is_merge));
+            true
+        }).unwrap();
+        assert_eq!(seen, vec![(Some(b"refs/heads/master".to_vec()),
+                               Some(url.clone().into_bytes()), head, true)]);
+
+        // `git fetch <url> <sha>` records an entry without a reference name,
+        // and old clients wrote FETCH_HEAD with nothing but the target.
+        let fetch_head = format!("{}\t\t{}\n{}\n", head, url, head);
+        t!(t!(fs::File::create(repo.path().join("FETCH_HEAD")))
+               .write_all(fetch_head.as_bytes()));
+        let mut seen = Vec::new();
+        repo.fetchhead_foreach(|name, remote_url, id, is_merge| {
+            seen.push((name.map(|s| s.to_vec()), remote_url.map(|s| s.to_vec()),
+                       *id, is_merge));
+            true
+        }).unwrap();
+        assert_eq!(seen, vec![(None, Some(url.into_bytes()), head, true),
+                              (None, None, head, true)]);
+    }
+
+    #[test]
+    fn set_odb() {
+        let td = TempDir::new("test").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        let td2 = TempDir::new("test").unwrap();
+        let other = Repository::init(td2.path()).unwrap();
+
+        repo.set_odb(&other.odb().unwrap());
+        let id = repo.blob(b"foo").unwrap();
+        assert_eq!(repo.find_blob(id).unwrap().content(), b"foo");
+        assert_eq!(other.find_blob(id).unwrap().content(), b"foo");
+
+        repo.set_odb(&Odb::new().unwrap());
+        assert!(!repo.odb().unwrap().exists(id));
+    }
+
+    #[test]
+    fn set_refdb() {
+        let (_td, repo) = ::test::repo_init();
+        let (_td2, other) = ::test::repo_init();
+        let head = other.head().unwrap().target().unwrap();
+        other.reference("refs/heads/other", head, false, "create").unwrap();
+
+        repo.set_refdb(&other.refdb().unwrap());
+        let r = repo.find_reference("refs/heads/other").unwrap();
+        assert_eq!(r.target(), Some(head));
+    }
+
+    #[test]
+    fn state_sequencer_files() {
+        let (_td, repo) = ::test::repo_init();
+        let git = repo.path().to_path_buf();
+        assert_eq!(repo.state(), RepositoryState::Clean);
+
+        fs::create_dir(git.join("rebase-merge")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::RebaseMerge);
+        fs::File::create(git.join("rebase-merge/interactive")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::RebaseInteractive);
+        fs::remove_dir_all(git.join("rebase-merge")).unwrap();
+
+        fs::create_dir(git.join("rebase-apply")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::ApplyMailboxOrRebase);
+        fs::File::create(git.join("rebase-apply/applying")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::ApplyMailbox);
+        fs::remove_file(git.join("rebase-apply/applying")).unwrap();
+        fs::File::create(git.join("rebase-apply/rebasing")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::Rebase);
+        fs::remove_dir_all(git.join("rebase-apply")).unwrap();
+
+        fs::File::create(git.join("CHERRY_PICK_HEAD")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::CherryPick);
+        fs::create_dir(git.join("sequencer")).unwrap();
+        fs::File::create(git.join("sequencer/todo")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::CherryPickSequence);
+        fs::remove_file(git.join("CHERRY_PICK_HEAD")).unwrap();
+
+        fs::File::create(git.join("REVERT_HEAD")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::RevertSequence);
+        fs::remove_dir_all(git.join("sequencer")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::Revert);
+        fs::remove_file(git.join("REVERT_HEAD")).unwrap();
+
+        fs::File::create(git.join("BISECT_LOG")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::Bisect);
+        fs::remove_file(git.join("BISECT_LOG")).unwrap();
+        assert_eq!(repo.state(), RepositoryState::Clean);
+    }
+
     #[test]
     fn smoke_reference_has_log_ensure_log() {
         let (_td, repo) = ::test::repo_init();
@@ -2540,4 +3881,266 @@ mod tests {
         let _ = repo.clear_ignore_rules();
         assert!(!repo.is_path_ignored(Path::new("/foo")).unwrap());
     }
+
+    #[test]
+    fn config_snapshot() {
+        let (_td, repo) = ::test::repo_init();
+        let mut config = repo.config().unwrap();
+        config.set_str("remote.origin.url", "https://example.com/a").unwrap();
+        config.set_str("remote.origin.fetch", "+refs/*:refs/*").unwrap();
+
+        let snapshot = repo.config_snapshot().unwrap();
+        config.set_str("remote.origin.url", "https://example.com/b").unwrap();
+        config.remove("remote.origin.fetch").unwrap();
+
+        assert_eq!(snapshot.get_str("remote.origin.url").unwrap(),
+                   "https://example.com/a");
+        assert_eq!(snapshot.get_str("remote.origin.fetch").unwrap(),
+                   "+refs/*:refs/*");
+        assert_eq!(snapshot.get_str("user.name").unwrap(), "name");
+        assert!(snapshot.get_str("foo.bar").is_err());
+        let fresh = repo.config_snapshot().unwrap();
+        assert_eq!(fresh.get_str("remote.origin.url").unwrap(),
+                   "https://example.com/b");
+    }
+
+    #[test]
+    fn blob_path_filters() {
+        let (td, repo) = ::test::repo_init();
+        repo.config().unwrap().set_bool("core.autocrlf", true).unwrap();
+        let path = td.path().join("crlf.txt");
+        fs::File::create(&path).unwrap().write_all(b"a\r\nb\r\n").unwrap();
+
+        let id = repo.blob_path(&path).unwrap();
+        assert_eq!(repo.find_blob(id).unwrap().content(), b"a\nb\n");
+    }
+
+    #[test]
+    fn merge_file_from_index() {
+        let (_td, repo) = ::test::repo_init();
+        let tree = |data: &[u8]| ::test::single_file_tree(&repo, data);
+        let ancestor = tree(b"a\nb\nc\n");
+        let ours = tree(b"a\nours\nc\n");
+        let theirs = tree(b"a\ntheirs\nc\n");
+        let index = repo.merge_trees(&ancestor, &ours, &theirs, None).unwrap();
+        let stages = index.iter().collect::<Vec<_>>();
+        assert_eq!(stages.len(), 3);
+
+        let mut opts = ::MergeFileOptions::new();
+        opts.our_label("ours").their_label("theirs");
+        let result = repo.merge_file_from_index(Some(&stages[0]),
+                                                Some(&stages[1]),
+                                                Some(&stages[2]),
+                                                Some(&opts)).unwrap();
+        assert!(!result.is_automergeable());
+        assert_eq!(result.path(), Some("file"));
+        assert_eq!(result.mode(), 0o100644);
+        assert_eq!(result.content(),
+                   &b"a\n<<<<<<< ours\nours\n=======\ntheirs\n\
+                      >>>>>>> theirs\nc\n"[..]);
+    }
+
+    #[test]
+    fn merge_trees_file_favor() {
+        let (_td, repo) = ::test::repo_init();
+        let tree = |data: &[u8]| ::test::single_file_tree(&repo, data);
+        let ancestor = tree(b"a\nb\nc\n");
+        let ours = tree(b"a\nours\nc\n");
+        let theirs = tree(b"a\ntheirs\nc\n");
+        let merged = |favor, diff3| {
+            let mut opts = ::MergeOptions::new();
+            opts.file_favor(favor).diff3_style(diff3);
+            let index = repo.merge_trees(&ancestor, &ours, &theirs,
+                                         Some(&opts)).unwrap();
+            if index.has_conflicts() {
+                return None
+            }
+            let id = index.get_path(Path::new("file"), 0).unwrap().id;
+            Some(repo.find_blob(id).unwrap().content().to_vec())
+        };
+
+        assert_eq!(merged(::FileFavor::Normal, false), None);
+        assert_eq!(merged(::FileFavor::Ours, false).unwrap(),
+                   b"a\nours\nc\n");
+        assert_eq!(merged(::FileFavor::Theirs, true).unwrap(),
+                   b"a\ntheirs\nc\n");
+        assert_eq!(merged(::FileFavor::Union, false).unwrap(),
+                   b"a\nours\ntheirs\nc\n");
+    }
+
+    // Creates a merge commit of two branches off the initial commit, one
+    // adding `main` and the other adding `side`, without moving HEAD.
+    fn merge_commit_init() -> (TempDir, Repository, Oid) {
+        let (td, repo) = ::test::repo_init();
+        let merge = {
+            let sig = repo.signature().unwrap();
+            let base = repo.head().unwrap().peel_to_commit().unwrap();
+            let tree = |files: &[&str]| {
+                let mut builder = repo.treebuilder(None).unwrap();
+                for file in files {
+                    let blob = repo.blob(file.as_bytes()).unwrap();
+                    builder.insert(file, blob, 0o100644).unwrap();
+                }
+                repo.find_tree(builder.write().unwrap()).unwrap()
+            };
+            let commit = |tree, parents: &[&::Commit]| {
+                let id = repo.commit(None, &sig, &sig, "commit", &tree,
+                                     parents).unwrap();
+                repo.find_commit(id).unwrap()
+            };
+            let main = commit(tree(&["main"]), &[&base]);
+            let side = commit(tree(&["side"]), &[&base]);
+            commit(tree(&["main", "side"]), &[&main, &side]).id()
+        };
+        (td, repo, merge)
+    }
+
+    #[test]
+    fn checkout_merged_index() {
+        let (td, repo, merge) = merge_commit_init();
+        let merge = repo.find_commit(merge).unwrap();
+        let main = merge.parent(0).unwrap();
+        let side = merge.parent(1).unwrap();
+
+        let mut index = repo.merge_commits(&main, &side, None).unwrap();
+        assert!(!index.has_conflicts());
+        repo.checkout_index(Some(&mut index),
+                            Some(CheckoutBuilder::new().force())).unwrap();
+        let mut contents = String::new();
+        fs::File::open(td.path().join("main")).unwrap()
+                 .read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "main");
+        assert!(td.path().join("side").exists());
+    }
+
+    #[test]
+    fn cherrypick_merge_mainline() {
+        let (td, repo, merge) = merge_commit_init();
+        let merge = repo.find_commit(merge).unwrap();
+
+        assert!(repo.cherrypick(&merge, None).is_err());
+
+        let mut opts = ::CherrypickOptions::new();
+        opts.mainline(1);
+        repo.cherrypick(&merge, Some(&mut opts)).unwrap();
+        assert_eq!(repo.state(), RepositoryState::CherryPick);
+        assert!(td.path().join("side").exists());
+        assert!(!td.path().join("main").exists());
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("side"), 0).is_some());
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let index = repo.cherrypick_commit(&merge, &head, 2, None).unwrap();
+        assert!(index.get_path(Path::new("main"), 0).is_some());
+        assert!(index.get_path(Path::new("side"), 0).is_none());
+    }
+
+    #[test]
+    fn revert_merge_mainline() {
+        let (_td, repo, merge) = merge_commit_init();
+        let merge = repo.find_commit(merge).unwrap();
+
+        let index = repo.revert_commit(&merge, &merge, 1, None).unwrap();
+        assert!(index.get_path(Path::new("main"), 0).is_some());
+        assert!(index.get_path(Path::new("side"), 0).is_none());
+
+        repo.reset(merge.as_object(), ResetType::Hard, None).unwrap();
+        let mut opts = ::RevertOptions::new();
+        opts.mainline(2);
+        repo.revert(&merge, Some(&mut opts)).unwrap();
+        assert_eq!(repo.state(), RepositoryState::Revert);
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("main"), 0).is_none());
+        assert!(index.get_path(Path::new("side"), 0).is_some());
+    }
+
+    #[test]
+    fn cherrypick_and_revert_head() {
+        let (_td, repo, merge) = merge_commit_init();
+        let merge = repo.find_commit(merge).unwrap();
+        let side = merge.parent(1).unwrap();
+        let err = repo.cherrypick_head().unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        let err = repo.revert_head().unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+
+        repo.cherrypick(&side, None).unwrap();
+        assert_eq!(repo.cherrypick_head().unwrap(), side.id());
+        assert!(repo.revert_head().is_err());
+        repo.cleanup_state().unwrap();
+        assert!(repo.cherrypick_head().is_err());
+
+        repo.reset(merge.as_object(), ResetType::Hard, None).unwrap();
+        let mut opts = ::RevertOptions::new();
+        opts.mainline(2);
+        repo.revert(&merge, Some(&mut opts)).unwrap();
+        assert_eq!(repo.revert_head().unwrap(), merge.id());
+        assert!(repo.cherrypick_head().is_err());
+    }
+
+    #[test]
+    fn commit_batch() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = Signature::new("name", "email", &Time::new(1000, 0)).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = head.tree_id();
+
+        let specs = (0..100).map(|i| {
+            let mut spec = CommitSpec::new(&sig, &sig, "batch", tree);
+            if i == 0 {
+                spec.parent(head.id());
+            } else {
+                spec.batch_parent(i - 1);
+            }
+            spec
+        }).collect::<Vec<_>>();
+        let ids = repo.commit_batch(&specs).unwrap();
+        assert_eq!(ids.len(), 100);
+
+        let tree = head.tree().unwrap();
+        let mut parent = head;
+        for _ in 0..100 {
+            let id = repo.commit(None, &sig, &sig, "batch", &tree,
+                                 &[&parent]).unwrap();
+            parent = repo.find_commit(id).unwrap();
+        }
+        assert_eq!(parent.id(), ids[99]);
+        let last = repo.find_commit(ids[99]).unwrap();
+        assert_eq!(last.parent_id(0).unwrap(), ids[98]);
+
+        let mut spec = CommitSpec::new(&sig, &sig, "forward", tree.id());
+        spec.batch_parent(0);
+        assert!(repo.commit_batch(&[spec]).is_err());
+    }
+
+    #[test]
+    fn branch_ahead_behind() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let head = t!(t!(repo.head()).peel_to_commit());
+        let tree = t!(head.tree());
+        t!(repo.remote("origin", "https://example.com/repo"));
+        t!(repo.reference("refs/remotes/origin/master", head.id(), false,
+                          "remote"));
+        let err = repo.branch_ahead_behind("master").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+
+        let mut branch = t!(repo.find_branch("master", BranchType::Local));
+        t!(branch.set_upstream(Some("origin/master")));
+        assert_eq!(t!(repo.branch_ahead_behind("master")), (0, 0));
+
+        let extend = |refname: &str, n: usize| {
+            for i in 0..n {
+                let parent = t!(t!(repo.find_reference(refname))
+                                    .peel_to_commit());
+                t!(repo.commit(Some(refname), &sig, &sig,
+                               &format!("{} {}", refname, i), &tree,
+                               &[&parent]));
+            }
+        };
+        extend("refs/heads/master", 2);
+        extend("refs/remotes/origin/master", 3);
+        assert_eq!(t!(repo.branch_ahead_behind("master")), (2, 3));
+        assert!(repo.branch_ahead_behind("missing").is_err());
+    }
 }
@@ -0,0 +1,84 @@
+use std::mem;
+use std::ptr;
+
+use build::CheckoutBuilder;
+use merge::MergeOptions;
+use raw;
+
+/// Options to specify when reverting
+pub struct RevertOptions<'cb> {
+    mainline: u32,
+    checkout_builder: Option<CheckoutBuilder<'cb>>,
+    merge_opts: Option<MergeOptions>,
+}
+
+impl<'cb> Default for RevertOptions<'cb> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'cb> RevertOptions<'cb> {
+    /// Creates a default set of revert options
+    pub fn new() -> RevertOptions<'cb> {
+        RevertOptions {
+            mainline: 0,
+            checkout_builder: None,
+            merge_opts: None,
+        }
+    }
+
+    /// Set the mainline value
+    ///
+    /// When reverting a merge commit this is the 1-based number of the
+    /// parent to diff against. It must be left at 0 for non-merge commits.
+    pub fn mainline(&mut self, mainline: u32) -> &mut RevertOptions<'cb> {
+        self.mainline = mainline;
+        self
+    }
+
+    /// Set the checkout builder
+    pub fn checkout_builder(&mut self, cb: CheckoutBuilder<'cb>)
+                            -> &mut RevertOptions<'cb> {
+        self.checkout_builder = Some(cb);
+        self
+    }
+
+    /// Set the merge options
+    pub fn merge_opts(&mut self, merge_opts: MergeOptions)
+                      -> &mut RevertOptions<'cb> {
+        self.merge_opts = Some(merge_opts);
+        self
+    }
+
+    /// Obtain the raw struct
+    ///
+    /// The returned structure borrows data from these options and must not
+    /// outlive them.
+    pub fn raw(&mut self) -> raw::git_revert_options {
+        unsafe {
+            let mut checkout_opts: raw::git_checkout_options = mem::zeroed();
+            raw::git_checkout_init_options(&mut checkout_opts,
+                                           raw::GIT_CHECKOUT_OPTIONS_VERSION);
+            if let Some(ref mut cb) = self.checkout_builder {
+                cb.configure(&mut checkout_opts);
+            }
+
+            let mut merge_opts: raw::git_merge_options = mem::zeroed();
+            raw::git_merge_init_options(&mut merge_opts,
+                                        raw::GIT_MERGE_OPTIONS_VERSION);
+            if let Some(ref opts) = self.merge_opts {
+                ptr::copy(opts.raw(), &mut merge_opts, 1);
+            }
+
+            let mut revert_opts: raw::git_revert_options = mem::zeroed();
+            raw::git_revert_init_options(&mut revert_opts,
+                                         raw::GIT_REVERT_OPTIONS_VERSION);
+            revert_opts.mainline = self.mainline;
+            revert_opts.checkout_opts = checkout_opts;
+            revert_opts.merge_opts = merge_opts;
+
+            revert_opts
+        }
+    }
+}
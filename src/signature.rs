@@ -96,6 +96,16 @@ impl<'a> Signature<'a> {
             me.clone()
         }
     }
+
+    /// Test whether this signature has the same name, email, and time
+    /// (including the timezone offset) as `other`.
+    pub fn matches(&self, other: &Signature) -> bool {
+        let (a, b) = (self.when(), other.when());
+        self.name_bytes() == other.name_bytes() &&
+            self.email_bytes() == other.email_bytes() &&
+            a.seconds() == b.seconds() &&
+            a.offset_minutes() == b.offset_minutes()
+    }
 }
 
 impl<'a> Binding for Signature<'a> {
@@ -172,4 +182,30 @@ mod tests {
         drop(s.clone());
         drop(s.to_owned());
     }
+
+    #[test]
+    fn owned_outlives_commit() {
+        let (_td, repo) = ::test::repo_init();
+        let owned = {
+            let head = repo.head().unwrap().target().unwrap();
+            let commit = repo.find_commit(head).unwrap();
+            let author = commit.author();
+            let owned = author.to_owned();
+            assert!(owned.matches(&author));
+            owned
+        };
+        drop(repo);
+        assert_eq!(owned.name(), Some("name"));
+        assert_eq!(owned.email(), Some("email"));
+        assert!(owned.when().seconds() > 0);
+
+        let t = owned.when();
+        let same = Signature::new("name", "email", &t).unwrap();
+        assert!(owned.matches(&same));
+        let shifted = Time::new(t.seconds(), t.offset_minutes() + 60);
+        let other = Signature::new("name", "email", &shifted).unwrap();
+        assert!(!owned.matches(&other));
+        let other = Signature::new("other", "email", &t).unwrap();
+        assert!(!owned.matches(&other));
+    }
 }
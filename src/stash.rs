@@ -1,4 +1,4 @@
-use {raw, panic, Oid, StashApplyProgress};
+use {raw, panic, Oid, StashApplyFlags, StashApplyProgress};
 use std::ffi::{CStr};
 use util::{Binding};
 use libc::{c_int, c_char, size_t, c_void};
@@ -49,6 +49,14 @@ impl<'cb> StashApplyOptions<'cb> {
         self
     }
 
+    /// Set the flags used when applying the stash, replacing any flags set
+    /// previously.
+    pub fn flags(&mut self, flags: StashApplyFlags)
+                 -> &mut StashApplyOptions<'cb> {
+        self.raw_opts.flags = flags.bits() as raw::git_stash_apply_flags;
+        self
+    }
+
     /// Options to use when writing files to the working directory
     pub fn checkout_options(&mut self, opts: CheckoutBuilder<'cb>) -> &mut StashApplyOptions<'cb> {
         self.checkout_options = Some(opts);
@@ -142,7 +150,7 @@ mod tests {
     use std::fs;
     use std::path::Path;
     use test::{repo_init};
-    use {Repository, Status, StashFlags};
+    use {ErrorCode, Repository, Status, StashApplyFlags, StashFlags};
 
     fn make_stash<C>(next: C) where C: FnOnce(&mut Repository) {
         let (_td, mut repo) = repo_init();
@@ -207,4 +215,61 @@ mod tests {
             assert!(count_stash(repo) == 1)
         })
     }
+
+    #[test]
+    fn stash_pop_reinstate_index() {
+        let (td, mut repo) = repo_init();
+        let signature = repo.signature().unwrap();
+
+        fs::File::create(&td.path().join("staged.txt")).unwrap()
+            .write("data".as_bytes()).unwrap();
+        let rel_p = Path::new("staged.txt");
+        let mut index = repo.index().unwrap();
+        index.add_path(rel_p).unwrap();
+        index.write().unwrap();
+        assert!(repo.status_file(rel_p).unwrap() == Status::INDEX_NEW);
+
+        repo.stash_save(&signature, "staged", None).unwrap();
+        assert!(repo.status_file(rel_p).is_err());
+
+        let mut options = StashApplyOptions::new();
+        options.flags(StashApplyFlags::REINSTATE_INDEX);
+        repo.stash_pop(0, Some(&mut options)).unwrap();
+        assert!(count_stash(&mut repo) == 0);
+        assert!(repo.status_file(rel_p).unwrap() == Status::INDEX_NEW);
+    }
+
+    #[test]
+    fn stash_pop_conflict() {
+        let (td, mut repo) = repo_init();
+        let signature = repo.signature().unwrap();
+        let path = td.path().join("file");
+        let rel_p = Path::new("file");
+        let commit = |repo: &Repository, contents: &str| {
+            fs::File::create(&path).unwrap()
+                .write(contents.as_bytes()).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(rel_p).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &signature, &signature, contents,
+                        &tree, &[&head]).unwrap();
+        };
+        commit(&repo, "base\n");
+
+        fs::File::create(&path).unwrap()
+            .write("stashed\n".as_bytes()).unwrap();
+        repo.stash_save(&signature, "conflict", None).unwrap();
+        commit(&repo, "committed\n");
+
+        let err = repo.stash_pop(0, None).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Conflict);
+        assert_eq!(count_stash(&mut repo), 1);
+        assert!(repo.index().unwrap().has_conflicts());
+        assert!(repo.status_file(rel_p).unwrap().contains(Status::CONFLICTED));
+
+        let err = repo.stash_apply(0, None).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Conflict);
+    }
 }
@@ -107,6 +107,24 @@ impl<'repo> Submodule<'repo> {
         Ok(())
     }
 
+    /// Set up the subrepository for a submodule in preparation for clone.
+    ///
+    /// This function can be called to init and set up a submodule repository
+    /// from a submodule in preparation to clone it from its remote.
+    ///
+    /// If `use_gitlink` is `true` the repository's `.git` directory is placed
+    /// in the superproject's `.git/modules` directory and linked to from the
+    /// working directory, otherwise it's contained in the working directory.
+    pub fn repo_init(&mut self, use_gitlink: bool)
+                     -> Result<Repository, Error> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_submodule_repo_init(&mut raw, self.raw,
+                                                   use_gitlink));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
     /// Open the repository for a submodule.
     ///
     /// This will only work if the submodule is checked out into the working
@@ -263,11 +281,12 @@ impl<'cb> Default for SubmoduleUpdateOptions<'cb> {
 #[cfg(test)]
 mod tests {
     use std::path::Path;
-    use std::fs;
+    use std::fs::{self, File};
+    use std::io::prelude::*;
     use tempdir::TempDir;
     use url::Url;
 
-    use Repository;
+    use {Config, Repository, SubmoduleIgnore, SubmoduleUpdate};
     use SubmoduleUpdateOptions;
 
     #[test]
@@ -299,6 +318,85 @@ mod tests {
         s.reload(true).unwrap();
     }
 
+    #[test]
+    fn init_and_repo_init() {
+        let td = TempDir::new("test").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        let mut file = File::create(td.path().join(".gitmodules")).unwrap();
+        file.write_all(b"[submodule \"foo\"]\n\
+                         \tpath = foo\n\
+                         \turl = /path/to/foo\n").unwrap();
+        drop(file);
+        let config_path = repo.path().join("config");
+        let url = |path: &Path| {
+            Config::open(path).unwrap().get_string("submodule.foo.url")
+        };
+
+        let mut s = repo.find_submodule("foo").unwrap();
+        assert!(url(&config_path).is_err());
+        s.init(false).unwrap();
+        assert_eq!(url(&config_path).unwrap(), "/path/to/foo");
+
+        let sub = s.repo_init(true).unwrap();
+        assert!(td.path().join("foo/.git").is_file());
+        assert!(repo.path().join("modules/foo").is_dir());
+        assert_eq!(::test::realpath(sub.workdir().unwrap()).unwrap(),
+                   ::test::realpath(&td.path().join("foo")).unwrap());
+    }
+
+    #[test]
+    fn set_config() {
+        let (td, repo) = ::test::repo_init();
+        let mut s = repo.submodule("/path/to/foo", Path::new("foo"), true)
+                        .unwrap();
+        s.init(false).unwrap();
+        drop(s);
+
+        repo.submodule_set_url("foo", "https://example.com/foo").unwrap();
+        repo.submodule_set_branch("foo", "stable").unwrap();
+        repo.submodule_set_ignore("foo", SubmoduleIgnore::Untracked).unwrap();
+        repo.submodule_set_update("foo", SubmoduleUpdate::Rebase).unwrap();
+
+        let gitmodules = Config::open(&td.path().join(".gitmodules")).unwrap();
+        let get = |name| gitmodules.get_string(name).unwrap();
+        assert_eq!(get("submodule.foo.url"), "https://example.com/foo");
+        assert_eq!(get("submodule.foo.branch"), "stable");
+        assert_eq!(get("submodule.foo.ignore"), "untracked");
+        assert_eq!(get("submodule.foo.update"), "rebase");
+
+        let mut s = repo.find_submodule("foo").unwrap();
+        s.sync().unwrap();
+        let config = Config::open(&repo.path().join("config")).unwrap();
+        assert_eq!(config.get_string("submodule.foo.url").unwrap(),
+                   "https://example.com/foo");
+        let s = repo.find_submodule("foo").unwrap();
+        assert_eq!(s.url(), Some("https://example.com/foo"));
+        assert_eq!(s.branch(), Some("stable"));
+    }
+
+    #[test]
+    fn foreach() {
+        let td = TempDir::new("test").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        repo.submodule("/path/to/foo", Path::new("foo"), true).unwrap();
+        repo.submodule("/path/to/bar", Path::new("bar"), true).unwrap();
+
+        let mut seen = Vec::new();
+        repo.submodule_foreach(|submodule, name| {
+            assert_eq!(submodule.name(), name);
+            seen.push((name.unwrap().to_string(),
+                       submodule.url().unwrap().to_string()));
+            true
+        }).unwrap();
+        seen.sort();
+        assert_eq!(seen, vec![("bar".to_string(), "/path/to/bar".to_string()),
+                              ("foo".to_string(), "/path/to/foo".to_string())]);
+
+        let mut count = 0;
+        repo.submodule_foreach(|_, _| { count += 1; false }).unwrap();
+        assert_eq!(count, 1);
+    }
+
     #[test]
     fn add_a_submodule() {
         let (_td, repo1) = ::test::repo_init();
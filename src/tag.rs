@@ -47,6 +47,8 @@ impl<'repo> Tag<'repo> {
     }
 
     /// Recursively peel a tag until a non tag git_object is found
+    ///
+    /// Unlike `target`, this follows chains of tags pointing at other tags.
     pub fn peel(&self) -> Result<Object<'repo>, Error> {
         let mut ret = ptr::null_mut();
         unsafe {
@@ -188,4 +190,22 @@ mod tests {
         let tags = t!(repo.tag_names(Some("b*")));
         assert_eq!(tags.len(), 0);
     }
+
+    #[test]
+    fn tag_of_tag() {
+        let (_td, repo) = ::test::repo_init();
+        let id = t!(repo.head()).target().unwrap();
+        let commit = t!(repo.find_object(id, None));
+        let sig = t!(repo.signature());
+        let inner_id = t!(repo.tag("inner", &commit, &sig, "inner", false));
+        let inner = t!(repo.find_object(inner_id, None));
+        let outer_id = t!(repo.tag("outer", &inner, &sig, "outer", false));
+        let outer = t!(repo.find_tag(outer_id));
+
+        assert_eq!(outer.target_id(), inner_id);
+        assert_eq!(outer.target_type(), Some(::ObjectType::Tag));
+        let peeled = t!(outer.peel());
+        assert_eq!(peeled.id(), id);
+        assert_eq!(peeled.kind(), Some(::ObjectType::Commit));
+    }
 }
@@ -1,11 +1,12 @@
+use std::fs::{self, File};
 use std::path::{Path, PathBuf};
-use std::io;
+use std::io::{self, Write};
 #[cfg(unix)]
 use std::ptr;
 use tempdir::TempDir;
 use url::Url;
 
-use Repository;
+use {Repository, Tree};
 
 macro_rules! t {
     ($e:expr) => (match $e {
@@ -32,6 +33,31 @@ pub fn repo_init() -> (TempDir, Repository) {
     (td, repo)
 }
 
+pub fn single_file_tree<'a>(repo: &'a Repository, data: &[u8]) -> Tree<'a> {
+    let blob = repo.blob(data).unwrap();
+    let mut builder = repo.treebuilder(None).unwrap();
+    builder.insert("file", blob, 0o100644).unwrap();
+    repo.find_tree(builder.write().unwrap()).unwrap()
+}
+
+// Lays out a linked worktree by hand, as `git worktree add` would, returning
+// its working directory and its administrative directory.
+pub fn worktree_init(repo: &Repository, name: &str) -> (PathBuf, PathBuf) {
+    let wt = repo.workdir().unwrap().join(name);
+    let admin = repo.path().join("worktrees").join(name);
+    fs::create_dir_all(&wt).unwrap();
+    fs::create_dir_all(&admin).unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+    let write = |path: &Path, contents: &str| {
+        File::create(path).unwrap().write_all(contents.as_bytes()).unwrap();
+    };
+    write(&admin.join("HEAD"), &format!("{}\n", head));
+    write(&admin.join("commondir"), "../..\n");
+    write(&admin.join("gitdir"), &format!("{}\n", wt.join(".git").display()));
+    write(&wt.join(".git"), &format!("gitdir: {}\n", admin.display()));
+    (wt, admin)
+}
+
 pub fn path2url(path: &Path) -> String {
     Url::from_file_path(path).unwrap().to_string()
 }
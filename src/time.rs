@@ -11,6 +11,20 @@ pub struct Time {
     raw: raw::git_time,
 }
 
+/// Formats in which a `Time` can be rendered with `Time::format`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// The format used by `git log` by default, for example
+    /// `Thu Apr 7 15:13:13 2005 -0700`.
+    Default,
+    /// Strict ISO 8601, as with `git log --date=iso-strict`, for example
+    /// `2005-04-07T15:13:13-07:00`.
+    Iso8601,
+    /// RFC 2822, as used in email headers and by `git log --date=rfc`, for
+    /// example `Thu, 7 Apr 2005 15:13:13 -0700`.
+    Rfc2822,
+}
+
 /// Time structure used in a git index entry.
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct IndexTime {
@@ -37,7 +51,74 @@ impl Time {
 
     /// Return whether the offset was positive or negative. Primarily useful
     /// in case the offset is specified as a negative zero.
-    pub fn sign(&self) -> char { self.raw.offset as u8 as char }
+    pub fn sign(&self) -> char { self.raw.sign as u8 as char }
+
+    /// Render this time in the given format.
+    ///
+    /// The time is shown in the timezone given by its offset, as git does.
+    pub fn format(&self, format: TimeFormat) -> String {
+        let local = self.seconds() + self.offset_minutes() as i64 * 60;
+        let days = div_floor(local, 86400);
+        let secs = local - days * 86400;
+        let (year, month, day) = civil_from_days(days);
+        let (hour, min, sec) = (secs / 3600, secs / 60 % 60, secs % 60);
+        let weekday = WEEKDAYS[((days + 4) % 7 + 7) as usize % 7];
+        let month_name = MONTHS[month as usize - 1];
+
+        let sign = if self.sign() == '-' || self.offset_minutes() < 0 {
+            '-'
+        } else {
+            '+'
+        };
+        let offset = self.offset_minutes().abs();
+        let (off_hour, off_min) = (offset / 60, offset % 60);
+
+        match format {
+            TimeFormat::Default => {
+                format!("{} {} {} {:02}:{:02}:{:02} {} {}{:02}{:02}",
+                        weekday, month_name, day, hour, min, sec, year,
+                        sign, off_hour, off_min)
+            }
+            TimeFormat::Iso8601 => {
+                format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+                        year, month, day, hour, min, sec,
+                        sign, off_hour, off_min)
+            }
+            TimeFormat::Rfc2822 => {
+                format!("{}, {} {} {} {:02}:{:02}:{:02} {}{:02}{:02}",
+                        weekday, day, month_name, year, hour, min, sec,
+                        sign, off_hour, off_min)
+            }
+        }
+    }
+}
+
+static WEEKDAYS: [&'static str; 7] = [
+    "Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat",
+];
+static MONTHS: [&'static str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun",
+    "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+fn div_floor(a: i64, b: i64) -> i64 {
+    let d = a / b;
+    if (a % b != 0) && ((a < 0) != (b < 0)) { d - 1 } else { d }
+}
+
+// Converts a number of days since 1970-01-01 to a (year, month, day) date in
+// the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = div_floor(z, 146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
 }
 
 impl PartialOrd for Time {
@@ -98,3 +179,33 @@ impl Ord for IndexTime {
         me.cmp(&other)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Time, TimeFormat};
+
+    #[test]
+    fn format() {
+        let time = Time::new(1112911993, -420);
+        assert_eq!(time.sign(), '-');
+        assert_eq!(time.format(TimeFormat::Default),
+                   "Thu Apr 7 15:13:13 2005 -0700");
+        assert_eq!(time.format(TimeFormat::Iso8601),
+                   "2005-04-07T15:13:13-07:00");
+        assert_eq!(time.format(TimeFormat::Rfc2822),
+                   "Thu, 7 Apr 2005 15:13:13 -0700");
+
+        let time = Time::new(1112911993, -90);
+        assert_eq!(time.format(TimeFormat::Iso8601),
+                   "2005-04-07T20:43:13-01:30");
+
+        let time = Time::new(0, 60);
+        assert_eq!(time.sign(), '+');
+        assert_eq!(time.format(TimeFormat::Rfc2822),
+                   "Thu, 1 Jan 1970 01:00:00 +0100");
+
+        let time = Time::new(-1, 0);
+        assert_eq!(time.format(TimeFormat::Iso8601),
+                   "1969-12-31T23:59:59+00:00");
+    }
+}
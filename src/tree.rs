@@ -3,13 +3,13 @@ use std::cmp::Ordering;
 use std::ffi::CString;
 use std::ops::Range;
 use std::marker;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::ptr;
 use std::str;
 use libc;
 
 use {raw, Oid, Repository, Error, Object, ObjectType};
-use util::{Binding, IntoCString};
+use util::{self, Binding, IntoCString};
 
 /// A structure to represent a git [tree][1]
 ///
@@ -103,6 +103,17 @@ impl<'repo> Tree<'repo> {
         }
     }
 
+    /// Collect the full paths of all blobs in this tree and its subtrees.
+    ///
+    /// Paths are relative to the root of this tree and are returned in the
+    /// order that a pre-order walk of the tree visits them. Subtrees are
+    /// looked up in `repo`; entries for submodules are skipped.
+    pub fn iter_paths(&self, repo: &Repository) -> Result<Vec<PathBuf>, Error> {
+        let mut paths = Vec::new();
+        try!(collect_paths(self, repo, Path::new(""), &mut paths));
+        Ok(paths)
+    }
+
     /// Casts this Tree to be usable as an `Object`
     pub fn as_object(&self) -> &Object<'repo> {
         unsafe {
@@ -119,6 +130,22 @@ impl<'repo> Tree<'repo> {
     }
 }
 
+fn collect_paths(tree: &Tree, repo: &Repository, prefix: &Path,
+                 paths: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in tree.iter() {
+        let path = prefix.join(util::bytes2path(entry.name_bytes()));
+        match entry.kind() {
+            Some(ObjectType::Blob) => paths.push(path),
+            Some(ObjectType::Tree) => {
+                let subtree = try!(repo.find_tree(entry.id()));
+                try!(collect_paths(&subtree, repo, &path, paths));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 impl<'repo> Binding for Tree<'repo> {
     type Raw = *mut raw::git_tree;
 
@@ -213,10 +240,12 @@ impl<'tree> TreeEntry<'tree> {
         unsafe { raw::git_tree_entry_filemode_raw(&*self.raw) as i32 }
     }
 
-    /// Convert this entry of any lifetime into an owned signature with a static
+    /// Convert this entry of any lifetime into an owned entry with a static
     /// lifetime.
     ///
-    /// This will use the `Clone::clone` implementation under the hood.
+    /// This will use the `Clone::clone` implementation under the hood, which
+    /// duplicates the entry with `git_tree_entry_dup`, so the result can
+    /// outlive the tree it came from.
     pub fn to_owned(&self) -> TreeEntry<'static> {
         unsafe {
             let me = mem::transmute::<&TreeEntry<'tree>, &TreeEntry<'static>>(self);
@@ -293,7 +322,7 @@ impl<'tree> ExactSizeIterator for TreeIter<'tree> {}
 
 #[cfg(test)]
 mod tests {
-    use {Repository,Tree,TreeEntry,ObjectType,Object};
+    use {Repository,Tree,TreeEntry,ObjectType,Object,Oid};
     use tempdir::TempDir;
     use std::fs::File;
     use std::io::prelude::*;
@@ -403,4 +432,51 @@ mod tests {
         repo.find_object(commit.tree_id(), None).unwrap().as_tree().unwrap();
         repo.find_object(commit.tree_id(), None).unwrap().into_tree().ok().unwrap();
     }
+
+    #[test]
+    fn iter_paths() {
+        let (_td, repo) = ::test::repo_init();
+        let blob = repo.blob(b"data").unwrap();
+        let tree = |entries: &[(&str, Oid, i32)]| {
+            let mut builder = repo.treebuilder(None).unwrap();
+            for &(name, id, mode) in entries {
+                builder.insert(name, id, mode).unwrap();
+            }
+            builder.write().unwrap()
+        };
+        let sub = tree(&[("c", blob, 0o100644), ("d", blob, 0o100755)]);
+        let dir = tree(&[("b", blob, 0o100644), ("sub", sub, 0o040000)]);
+        let root = tree(&[("a", blob, 0o100644), ("dir", dir, 0o040000),
+                          ("link", blob, 0o120000)]);
+
+        let root = repo.find_tree(root).unwrap();
+        let paths = root.iter_paths(&repo).unwrap();
+        assert_eq!(paths, [
+            Path::new("a"),
+            Path::new("dir/b"),
+            Path::new("dir/sub/c"),
+            Path::new("dir/sub/d"),
+            Path::new("link"),
+        ]);
+    }
+
+    #[test]
+    fn owned_entries_outlive_tree() {
+        let (_td, repo) = ::test::repo_init();
+        let blob = repo.blob(b"data").unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        for name in &["c", "a", "b"] {
+            builder.insert(name, blob, 0o100644).unwrap();
+        }
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+
+        let mut entries: Vec<TreeEntry<'static>> =
+            tree.iter().map(|e| e.to_owned()).collect();
+        drop(tree);
+        entries.sort();
+        let names = entries.iter().map(|e| e.name().unwrap())
+                           .collect::<Vec<_>>();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert!(entries.iter().all(|e| e.id() == blob));
+    }
 }
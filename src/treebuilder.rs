@@ -49,11 +49,15 @@ impl<'repo> TreeBuilder<'repo> {
     /// an object of a reasonable type (or any object at all).
     ///
     /// The mode given must be one of 0o040000, 0o100644, 0o100755, 0o120000 or
-    /// 0o160000 currently.
-    pub fn insert<P: IntoCString>(&mut self, filename: P, oid: Oid,
-                                  filemode: i32) -> Result<TreeEntry, Error> {
+    /// 0o160000 currently, or the corresponding `FileMode`. Entries with the
+    /// `FileMode::Commit` mode are gitlinks (such as submodules) and their
+    /// `oid` does not need to exist in this repository.
+    pub fn insert<P, M>(&mut self, filename: P, oid: Oid, filemode: M)
+                        -> Result<TreeEntry, Error>
+        where P: IntoCString, M: Into<i32>
+    {
         let filename = try!(filename.into_c_string());
-        let filemode = filemode as raw::git_filemode_t;
+        let filemode = filemode.into() as raw::git_filemode_t;
 
         let mut ret = ptr::null();
         unsafe {
@@ -132,7 +136,7 @@ impl<'repo> Drop for TreeBuilder<'repo> {
 
 #[cfg(test)]
 mod tests {
-    use ObjectType;
+    use {FileMode, ObjectType, Oid};
 
     #[test]
     fn smoke() {
@@ -196,4 +200,49 @@ mod tests {
         builder.filter(|_| false);
         assert_eq!(builder.len(), 0);
     }
+
+    #[test]
+    fn filter_write() {
+        let (_td, repo) = ::test::repo_init();
+
+        let mut builder = repo.treebuilder(None).unwrap();
+        let blob = repo.blob(b"data").unwrap();
+        builder.insert("a.txt", blob, 0o100644).unwrap();
+        builder.insert("b.tmp", blob, 0o100644).unwrap();
+        builder.insert("c.tmp", blob, 0o100644).unwrap();
+
+        builder.filter(|e| !e.name().unwrap().ends_with(".tmp"));
+        assert_eq!(builder.len(), 1);
+        let tree = builder.write().unwrap();
+        let tree = repo.find_tree(tree).unwrap();
+        assert_eq!(tree.len(), 1);
+        assert!(tree.get_name("a.txt").is_some());
+        assert!(tree.get_name("b.tmp").is_none());
+        assert!(tree.get_name("c.tmp").is_none());
+    }
+
+    #[test]
+    fn gitlink() {
+        let (_td, repo) = ::test::repo_init();
+
+        let mut builder = repo.treebuilder(None).unwrap();
+        let blob = repo.blob(b"data").unwrap();
+        let sub = Oid::from_str("0123456789abcdef0123456789abcdef01234567")
+            .unwrap();
+        builder.insert("sub", sub, FileMode::Commit).unwrap();
+        builder.insert("run.sh", blob, FileMode::BlobExecutable).unwrap();
+        builder.insert("link", blob, FileMode::Link).unwrap();
+        builder.insert("file", blob, FileMode::Blob).unwrap();
+        let tree = builder.write().unwrap();
+        let tree = repo.find_tree(tree).unwrap();
+
+        let entry = tree.get_name("sub").unwrap();
+        assert_eq!(entry.filemode(), 0o160000);
+        assert_eq!(entry.id(), sub);
+        assert_eq!(entry.kind(), Some(ObjectType::Commit));
+        assert_eq!(tree.get_name("run.sh").unwrap().filemode(), 0o100755);
+        assert_eq!(tree.get_name("link").unwrap().filemode(), 0o120000);
+        assert_eq!(tree.get_name("file").unwrap().filemode(), 0o100644);
+        assert_eq!(i32::from(FileMode::Tree), 0o040000);
+    }
 }
@@ -0,0 +1,74 @@
+use std::ptr;
+
+use {raw, Error, Repository};
+use util::Binding;
+
+/// A structure to represent a linked worktree of a repository.
+///
+/// Worktrees are looked up by name with `Repository::find_worktree`, and the
+/// names of all worktrees are listed by `Repository::worktrees`.
+pub struct Worktree {
+    raw: *mut raw::git_worktree,
+}
+
+impl Worktree {
+    /// Open the repository checked out in this worktree.
+    pub fn open(&self) -> Result<Repository, Error> {
+        let mut ret = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_repository_open_from_worktree(&mut ret,
+                                                             self.raw));
+            Ok(Binding::from_raw(ret))
+        }
+    }
+
+    /// Check that this worktree is still valid.
+    ///
+    /// A worktree is valid if its gitdir, parent repository and common
+    /// directory all still exist on disk.
+    pub fn validate(&self) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_worktree_validate(self.raw));
+        }
+        Ok(())
+    }
+}
+
+impl Binding for Worktree {
+    type Raw = *mut raw::git_worktree;
+
+    unsafe fn from_raw(raw: *mut raw::git_worktree) -> Worktree {
+        Worktree { raw: raw }
+    }
+    fn raw(&self) -> *mut raw::git_worktree { self.raw }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        unsafe { raw::git_worktree_free(self.raw) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn find_and_open() {
+        let (_td, repo) = ::test::repo_init();
+        assert_eq!(repo.worktrees().unwrap().len(), 0);
+        assert!(repo.find_worktree("wt").is_err());
+
+        let (wt, _admin) = ::test::worktree_init(&repo, "wt");
+        let head = repo.head().unwrap().target().unwrap();
+
+        let names = repo.worktrees().unwrap();
+        assert_eq!(names.iter().collect::<Vec<_>>(), [Some("wt")]);
+
+        let worktree = repo.find_worktree("wt").unwrap();
+        worktree.validate().unwrap();
+        let opened = worktree.open().unwrap();
+        assert!(opened.is_worktree());
+        assert_eq!(::test::realpath(opened.workdir().unwrap()).unwrap(),
+                   ::test::realpath(&wt).unwrap());
+        assert_eq!(opened.head().unwrap().target(), Some(head));
+    }
+}